@@ -0,0 +1,486 @@
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+
+use num_traits::Num;
+
+use crate::array::Array;
+use crate::shape_utils::broadcast_shapes;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView, StridedView};
+
+/// Applies `f` element-wise to `array`, collecting the results into a new
+/// contiguous array.
+pub(crate) fn map_values<T, StorageType, ViewType, F>(
+    array: &Array<StorageType, ViewType>,
+    f: F,
+) -> Array<StorageType, ContiguousView>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+    F: Fn(T) -> T,
+{
+    let storage = array
+        .storage()
+        .storage_get()
+        .expect("error while getting storage");
+    let data = array
+        .view()
+        .translate_iter()
+        .map(|i| f(storage[i].clone()))
+        .collect::<Vec<T>>();
+    Array::from_parts(StorageType::from(data), ContiguousView::new(array.shape()))
+}
+
+/// Broadcasts `lhs` and `rhs` against each other and applies `f` to every
+/// pair of elements, collecting the results into a new contiguous array.
+pub(crate) fn zip_values<T, StorageType, LhsView, RhsView, F>(
+    lhs: &Array<StorageType, LhsView>,
+    rhs: &Array<StorageType, RhsView>,
+    f: F,
+) -> Array<StorageType, ContiguousView>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+    LhsView: ArrayView,
+    RhsView: ArrayView,
+    F: Fn(T, T) -> T,
+{
+    let shape =
+        broadcast_shapes(&[lhs.shape(), rhs.shape()]).unwrap_or_else(|err| panic!("{}", err));
+    let lhs_view = StridedView::from_view(lhs.view())
+        .broadcast_to(&shape)
+        .unwrap_or_else(|err| panic!("{}", err));
+    let rhs_view = StridedView::from_view(rhs.view())
+        .broadcast_to(&shape)
+        .unwrap_or_else(|err| panic!("{}", err));
+
+    let lhs_storage = lhs
+        .storage()
+        .storage_get()
+        .expect("error while getting storage");
+    // Avoid taking a second guard on the same storage, a pending writer could
+    // otherwise wedge itself between the two read locks.
+    let rhs_storage = if lhs.storage().storage_ptr_eq(rhs.storage()) {
+        None
+    } else {
+        Some(
+            rhs.storage()
+                .storage_get()
+                .expect("error while getting storage"),
+        )
+    };
+    let rhs_slice = rhs_storage.as_deref().unwrap_or(&lhs_storage[..]);
+
+    let data = lhs_view
+        .translate_iter()
+        .zip(rhs_view.translate_iter())
+        .map(|(i, j)| f(lhs_storage[i].clone(), rhs_slice[j].clone()))
+        .collect::<Vec<T>>();
+    Array::from_parts(StorageType::from(data), ContiguousView::new(shape))
+}
+
+/// Panics if `view` maps several indices to the same element, as broadcast
+/// views do, since writing through it would be ambiguous.
+pub(crate) fn assert_writable<ViewType: ArrayView>(view: &ViewType) {
+    let overlapping = view
+        .shape()
+        .iter()
+        .zip(view.stride().iter())
+        .any(|(&dim, &stride)| dim > 1 && stride == 0);
+    if overlapping {
+        panic!(
+            "cannot write to a broadcast view with shape {:?} and stride {:?}",
+            view.shape(),
+            view.stride()
+        );
+    }
+}
+
+/// Combines every element of `array` with the next item of `values` through
+/// `f`, writing the result back into the shared storage.
+pub(crate) fn update_values<T, StorageType, ViewType, I, F>(
+    array: &mut Array<StorageType, ViewType>,
+    values: I,
+    f: F,
+) where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+    I: Iterator<Item = T>,
+    F: Fn(T, T) -> T,
+{
+    assert_writable(array.view());
+
+    let (storage, view) = array.parts_mut();
+    let mut storage = storage
+        .storage_get_mut()
+        .expect("error while getting storage");
+    for (i, value) in view.translate_iter().zip(values) {
+        let current = std::mem::replace(&mut storage[i], T::zero());
+        storage[i] = f(current, value);
+    }
+}
+
+/// Broadcasts `rhs` to the shape of `lhs` and combines the two in place.
+pub(crate) fn assign_values<T, StorageType, LhsView, RhsView, F>(
+    lhs: &mut Array<StorageType, LhsView>,
+    rhs: &Array<StorageType, RhsView>,
+    f: F,
+) where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+    LhsView: ArrayView,
+    RhsView: ArrayView,
+    F: Fn(T, T) -> T,
+{
+    let rhs_view = StridedView::from_view(rhs.view())
+        .broadcast_to(lhs.shape())
+        .unwrap_or_else(|err| panic!("{}", err));
+
+    if lhs.storage().storage_ptr_eq(rhs.storage()) {
+        // The write guard on the destination also covers the source, and the
+        // two views may overlap, so the source is copied out first.
+        let snapshot = rhs.to_vec();
+        let snapshot_view = StridedView::from_view(&ContiguousView::new(rhs.shape()))
+            .broadcast_to(lhs.shape())
+            .unwrap_or_else(|err| panic!("{}", err));
+        let values = snapshot_view.translate_iter().map(|j| snapshot[j].clone());
+        update_values(lhs, values, f);
+    } else {
+        let rhs_storage = rhs
+            .storage()
+            .storage_get()
+            .expect("error while getting storage");
+        let values = rhs_view.translate_iter().map(|j| rhs_storage[j].clone());
+        update_values(lhs, values, f);
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident) => {
+        impl<'a, 'b, T, StorageType, LhsView, RhsView> $trait<&'b Array<StorageType, RhsView>>
+            for &'a Array<StorageType, LhsView>
+        where
+            T: Num + Clone,
+            StorageType: Storage<Stored = T>,
+            LhsView: ArrayView,
+            RhsView: ArrayView,
+        {
+            type Output = Array<StorageType, ContiguousView>;
+
+            fn $method(self, rhs: &'b Array<StorageType, RhsView>) -> Self::Output {
+                zip_values(self, rhs, |a, b| a.$method(b))
+            }
+        }
+
+        impl<'b, T, StorageType, LhsView, RhsView> $trait<&'b Array<StorageType, RhsView>>
+            for Array<StorageType, LhsView>
+        where
+            T: Num + Clone,
+            StorageType: Storage<Stored = T>,
+            LhsView: ArrayView,
+            RhsView: ArrayView,
+        {
+            type Output = Array<StorageType, ContiguousView>;
+
+            fn $method(self, rhs: &'b Array<StorageType, RhsView>) -> Self::Output {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl<'a, T, StorageType, LhsView, RhsView> $trait<Array<StorageType, RhsView>>
+            for &'a Array<StorageType, LhsView>
+        where
+            T: Num + Clone,
+            StorageType: Storage<Stored = T>,
+            LhsView: ArrayView,
+            RhsView: ArrayView,
+        {
+            type Output = Array<StorageType, ContiguousView>;
+
+            fn $method(self, rhs: Array<StorageType, RhsView>) -> Self::Output {
+                self.$method(&rhs)
+            }
+        }
+
+        impl<T, StorageType, LhsView, RhsView> $trait<Array<StorageType, RhsView>>
+            for Array<StorageType, LhsView>
+        where
+            T: Num + Clone,
+            StorageType: Storage<Stored = T>,
+            LhsView: ArrayView,
+            RhsView: ArrayView,
+        {
+            type Output = Array<StorageType, ContiguousView>;
+
+            fn $method(self, rhs: Array<StorageType, RhsView>) -> Self::Output {
+                (&self).$method(&rhs)
+            }
+        }
+    };
+}
+
+macro_rules! impl_scalar_op {
+    ($trait:ident, $method:ident, $($scalar:ty),*) => {
+        $(
+            impl<'a, StorageType, ViewType> $trait<$scalar> for &'a Array<StorageType, ViewType>
+            where
+                StorageType: Storage<Stored = $scalar>,
+                ViewType: ArrayView,
+            {
+                type Output = Array<StorageType, ContiguousView>;
+
+                fn $method(self, rhs: $scalar) -> Self::Output {
+                    map_values(self, |a| a.$method(rhs))
+                }
+            }
+
+            impl<StorageType, ViewType> $trait<$scalar> for Array<StorageType, ViewType>
+            where
+                StorageType: Storage<Stored = $scalar>,
+                ViewType: ArrayView,
+            {
+                type Output = Array<StorageType, ContiguousView>;
+
+                fn $method(self, rhs: $scalar) -> Self::Output {
+                    (&self).$method(rhs)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_ops {
+    ($(($trait:ident, $method:ident)),*) => {
+        $(
+            impl_binary_op!($trait, $method);
+            impl_scalar_op!(
+                $trait, $method, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize,
+                f32, f64
+            );
+        )*
+    };
+}
+
+impl_ops!((Add, add), (Sub, sub), (Mul, mul), (Div, div), (Rem, rem));
+
+macro_rules! impl_assign_op {
+    ($trait:ident, $method:ident, $op:ident) => {
+        impl<'b, T, StorageType, LhsView, RhsView> $trait<&'b Array<StorageType, RhsView>>
+            for Array<StorageType, LhsView>
+        where
+            T: Num + Clone,
+            StorageType: Storage<Stored = T>,
+            LhsView: ArrayView,
+            RhsView: ArrayView,
+        {
+            fn $method(&mut self, rhs: &'b Array<StorageType, RhsView>) {
+                assign_values(self, rhs, |a, b| a.$op(b));
+            }
+        }
+
+        impl<T, StorageType, LhsView, RhsView> $trait<Array<StorageType, RhsView>>
+            for Array<StorageType, LhsView>
+        where
+            T: Num + Clone,
+            StorageType: Storage<Stored = T>,
+            LhsView: ArrayView,
+            RhsView: ArrayView,
+        {
+            fn $method(&mut self, rhs: Array<StorageType, RhsView>) {
+                self.$method(&rhs);
+            }
+        }
+    };
+}
+
+macro_rules! impl_scalar_assign_op {
+    ($trait:ident, $method:ident, $op:ident, $($scalar:ty),*) => {
+        $(
+            impl<StorageType, ViewType> $trait<$scalar> for Array<StorageType, ViewType>
+            where
+                StorageType: Storage<Stored = $scalar>,
+                ViewType: ArrayView,
+            {
+                fn $method(&mut self, rhs: $scalar) {
+                    update_values(self, std::iter::repeat(rhs), |a, b| a.$op(b));
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_assign_ops {
+    ($(($trait:ident, $method:ident, $op:ident)),*) => {
+        $(
+            impl_assign_op!($trait, $method, $op);
+            impl_scalar_assign_op!(
+                $trait, $method, $op, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128,
+                usize, f32, f64
+            );
+        )*
+    };
+}
+
+impl_assign_ops!(
+    (AddAssign, add_assign, add),
+    (SubAssign, sub_assign, sub),
+    (MulAssign, mul_assign, mul),
+    (DivAssign, div_assign, div),
+    (RemAssign, rem_assign, rem)
+);
+
+impl<T, StorageType, ViewType> Neg for &Array<StorageType, ViewType>
+where
+    T: Num + Clone + Neg<Output = T>,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    type Output = Array<StorageType, ContiguousView>;
+
+    fn neg(self) -> Self::Output {
+        map_values(self, |a| -a)
+    }
+}
+
+impl<T, StorageType, ViewType> Neg for Array<StorageType, ViewType>
+where
+    T: Num + Clone + Neg<Output = T>,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    type Output = Array<StorageType, ContiguousView>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+    use crate::test_utils::arange;
+    use crate::thread_unsafe_storage::ThreadUnsafeStorage;
+
+    #[test]
+    fn add_same_shape() {
+        let a = arange(&[2, 3]);
+        let b = arange(&[2, 3]);
+        let c = &a + &b;
+        assert_eq!(c.shape(), &[2, 3]);
+        assert_eq!(c.to_vec(), vec![0, 2, 4, 6, 8, 10]);
+        let d = a - b;
+        assert_eq!(d.to_vec(), vec![0; 6]);
+    }
+
+    #[test]
+    fn mul_broadcast() {
+        let a = arange(&[2, 3]);
+        let b = arange(&[3]);
+        assert_eq!((&a * &b).to_vec(), vec![0, 1, 4, 0, 4, 10]);
+        let column = arange(&[2, 1]);
+        assert_eq!((&column * &b).to_vec(), vec![0, 0, 0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn ops_on_strided_views() {
+        let a = arange(&[2, 3]);
+        let sum = &a.t() + &a.slice(s![0, ..;-1]).reshape([3usize, 1]);
+        assert_eq!(sum.shape(), &[3, 2]);
+        assert_eq!(sum.to_vec(), vec![2, 5, 2, 5, 2, 5]);
+    }
+
+    #[test]
+    fn ops_with_aliased_storage() {
+        let a = arange(&[2, 2]);
+        assert_eq!((&a * &a.t()).to_vec(), vec![0, 2, 2, 9]);
+    }
+
+    #[test]
+    fn scalar_ops() {
+        let a = arange(&[4]);
+        assert_eq!((&a + 1).to_vec(), vec![1, 2, 3, 4]);
+        assert_eq!((&a * 3).to_vec(), vec![0, 3, 6, 9]);
+        assert_eq!((&a / 2).to_vec(), vec![0, 0, 1, 1]);
+        assert_eq!((&a % 3).to_vec(), vec![0, 1, 2, 0]);
+        assert_eq!((a - 1).to_vec(), vec![-1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn neg_and_div_floats() {
+        let a = Array::from_parts(
+            ThreadUnsafeStorage::from(vec![1.0f64, 2.0, 4.0]),
+            ContiguousView::new([3]),
+        );
+        assert_eq!((-&a).to_vec(), vec![-1.0, -2.0, -4.0]);
+        assert_eq!((&a / &a.slice(s![..1])).to_vec(), vec![1.0, 2.0, 4.0]);
+        assert_eq!((a / 2.0).to_vec(), vec![0.5, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn add_assign_visible_through_shared_storage() {
+        let a = arange(&[2, 3]);
+        let mut view = a.slice(s![.., 1..]);
+        view += 10;
+        assert_eq!(a.to_vec(), vec![0, 11, 12, 3, 14, 15]);
+
+        let b = Array::from_parts(
+            ThreadUnsafeStorage::from(vec![1.0f64, 2.0]),
+            ContiguousView::new([2]),
+        );
+        let mut alias = b.reshape([2usize]);
+        alias *= 4.0;
+        assert_eq!(b.to_vec(), vec![4.0, 8.0]);
+    }
+
+    #[test]
+    fn assign_ops_broadcast_strided_rhs() {
+        let mut a = arange(&[2, 3]);
+        let b = arange(&[3, 1]);
+        a -= b.t();
+        assert_eq!(a.to_vec(), vec![0, 0, 0, 3, 3, 3]);
+        a *= &arange(&[2, 1]);
+        assert_eq!(a.to_vec(), vec![0, 0, 0, 3, 3, 3]);
+        a %= 2;
+        assert_eq!(a.to_vec(), vec![0, 0, 0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn assign_ops_aliased_rhs() {
+        let mut a = arange(&[3, 3]);
+        let transposed = a.t();
+        a += &transposed;
+        assert_eq!(a.to_vec(), vec![0, 4, 8, 4, 8, 12, 8, 12, 16]);
+
+        let mut row = a.slice(s![1..]);
+        row /= a.slice(s![..1]) + 1;
+        assert_eq!(a.to_vec(), vec![0, 4, 8, 4, 1, 1, 8, 2, 1]);
+    }
+
+    #[test]
+    fn assign_to_broadcast_view_panics() {
+        let a = arange(&[3]);
+        let mut view = a.broadcast_to([2, 3]);
+        let result = std::panic::catch_unwind(move || view += 1);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn assign_incompatible_shapes_panic() {
+        let mut a = arange(&[3]);
+        let b = arange(&[2, 3]);
+        let result = std::panic::catch_unwind(move || a += &b);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn incompatible_shapes_panic() {
+        let a = arange(&[2, 3]);
+        let b = arange(&[2]);
+        let result = std::panic::catch_unwind(|| &a + &b);
+        assert!(result.is_err());
+    }
+}
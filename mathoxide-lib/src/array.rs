@@ -1,11 +1,16 @@
+use std::convert::TryFrom;
 use std::fmt;
+use std::iter::FromIterator;
 
-use num_traits::Num;
+use num_traits::{Float, Num, NumCast};
 
+use crate::arithmetic::{assert_writable, assign_values, update_values};
+use crate::array_iters::{AxisIter, AxisIterMut, IndexedIter, Iter, IterMut};
 use crate::formatter::{ArrayFormatter, VerboseFormatter};
-use crate::shape_utils::{infer_shape, ShapeDim};
+use crate::shape_utils::{infer_shape, normalize_axis, ShapeDim};
+use crate::slice::SliceInfoElem;
 use crate::storage::Storage;
-use crate::views::{ArrayView, ContiguousView};
+use crate::views::{ArrayView, ContiguousView, StridedView};
 
 pub struct Array<StorageType, ViewType> {
     storage: StorageType,
@@ -32,6 +37,22 @@ where
     StorageType: Storage<Stored = T>,
     ViewType: ArrayView,
 {
+    pub(crate) fn from_parts(storage: StorageType, view: ViewType) -> Self {
+        Array { storage, view }
+    }
+
+    pub(crate) fn storage(&self) -> &StorageType {
+        &self.storage
+    }
+
+    pub(crate) fn view(&self) -> &ViewType {
+        &self.view
+    }
+
+    pub(crate) fn parts_mut(&mut self) -> (&mut StorageType, &ViewType) {
+        (&mut self.storage, &self.view)
+    }
+
     pub fn storage_offset(&self) -> usize {
         self.view.offset()
     }
@@ -77,6 +98,169 @@ where
             view,
         }
     }
+
+    pub fn permute_axes<ListType: AsRef<[usize]>>(
+        &self,
+        axes: ListType,
+    ) -> Array<StorageType, StridedView> {
+        let view = StridedView::from_view(&self.view)
+            .permute_axes(axes)
+            .unwrap_or_else(|err| panic!("{}", err));
+        Array {
+            storage: self.storage.clone(),
+            view,
+        }
+    }
+
+    pub fn broadcast_to<ListType: AsRef<[usize]>>(
+        &self,
+        shape: ListType,
+    ) -> Array<StorageType, StridedView> {
+        let view = StridedView::from_view(&self.view)
+            .broadcast_to(shape)
+            .unwrap_or_else(|err| panic!("{}", err));
+        Array {
+            storage: self.storage.clone(),
+            view,
+        }
+    }
+
+    pub fn slice<InfoType: AsRef<[SliceInfoElem]>>(
+        &self,
+        info: InfoType,
+    ) -> Array<StorageType, StridedView> {
+        let view = StridedView::from_view(&self.view)
+            .slice(info)
+            .unwrap_or_else(|err| panic!("{}", err));
+        Array {
+            storage: self.storage.clone(),
+            view,
+        }
+    }
+
+    pub fn transpose(&self) -> Array<StorageType, StridedView> {
+        self.permute_axes((0..self.ndim()).rev().collect::<Vec<usize>>())
+    }
+
+    pub fn t(&self) -> Array<StorageType, StridedView> {
+        self.transpose()
+    }
+
+    /// Iterates over references to the elements in row-major order. The
+    /// storage stays locked for reading until the iterator is dropped.
+    pub fn iter(&self) -> Iter<'_, StorageType, ViewType> {
+        Iter::new(&self.storage, &self.view)
+    }
+
+    /// Like [`Array::iter`], but each element comes with its index.
+    pub fn indexed_iter(&self) -> IndexedIter<'_, StorageType, ViewType> {
+        IndexedIter::new(&self.storage, &self.view)
+    }
+
+    /// Iterates over mutable references to the elements in row-major order.
+    /// The storage stays locked for writing until the iterator is dropped,
+    /// and writes are visible through every array sharing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is a broadcast view.
+    pub fn iter_mut(&mut self) -> IterMut<'_, StorageType, ViewType> {
+        assert_writable(&self.view);
+        IterMut::new(&mut self.storage, &self.view)
+    }
+
+    /// Splits the view into one of `axis` alone and one of the remaining
+    /// axes, both starting at the same element.
+    fn split_axis(&self, axis: isize) -> (StridedView, StridedView) {
+        let axis = normalize_axis(axis, self.ndim()).unwrap_or_else(|err| panic!("{}", err));
+        let without_axis = |list: &[usize]| {
+            let mut list = list.to_vec();
+            list.remove(axis);
+            list
+        };
+        (
+            StridedView::new_with_offset(
+                [self.shape()[axis]],
+                [self.stride()[axis]],
+                self.storage_offset(),
+            ),
+            StridedView::new_with_offset(
+                without_axis(self.shape()),
+                without_axis(self.stride()),
+                self.storage_offset(),
+            ),
+        )
+    }
+
+    /// The sub-arrays at each index along `axis`, each with that axis
+    /// removed. The sub-arrays are views sharing storage with `self`.
+    pub fn axis_iter(&self, axis: isize) -> AxisIter<StorageType> {
+        let (along, rest) = self.split_axis(axis);
+        AxisIter::new(self.storage.clone(), &along, rest.shape(), rest.stride())
+    }
+
+    /// The sub-arrays along the first axis, such as the rows of a matrix.
+    pub fn outer_iter(&self) -> AxisIter<StorageType> {
+        self.axis_iter(0)
+    }
+
+    /// The 1-D views along `axis`, one for every index of the remaining
+    /// axes in row-major order. The lanes along the last axis of a matrix
+    /// are its rows and the lanes along the first axis its columns.
+    pub fn lanes(&self, axis: isize) -> AxisIter<StorageType> {
+        let (along, rest) = self.split_axis(axis);
+        AxisIter::new(self.storage.clone(), &rest, along.shape(), along.stride())
+    }
+
+    /// Like [`Array::axis_iter`], for writing through the sub-arrays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is a broadcast view.
+    pub fn axis_iter_mut(&mut self, axis: isize) -> AxisIterMut<'_, StorageType> {
+        assert_writable(&self.view);
+        AxisIterMut::new(self.axis_iter(axis))
+    }
+
+    /// Like [`Array::outer_iter`], for writing through the sub-arrays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is a broadcast view.
+    pub fn outer_iter_mut(&mut self) -> AxisIterMut<'_, StorageType> {
+        self.axis_iter_mut(0)
+    }
+
+    /// Like [`Array::lanes`], for writing through the lanes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is a broadcast view.
+    pub fn lanes_mut(&mut self, axis: isize) -> AxisIterMut<'_, StorageType> {
+        assert_writable(&self.view);
+        AxisIterMut::new(self.lanes(axis))
+    }
+}
+
+/// An array of any view type over `StorageType`. Functions taking a list of
+/// arrays accept `&[&dyn AsStrided<StorageType>]`, so contiguous arrays can be
+/// passed alongside slices and transposes.
+pub trait AsStrided<StorageType> {
+    /// The same array behind a [`StridedView`], sharing storage.
+    fn as_strided(&self) -> Array<StorageType, StridedView>;
+}
+
+impl<StorageType, ViewType> AsStrided<StorageType> for Array<StorageType, ViewType>
+where
+    StorageType: Storage,
+    ViewType: ArrayView,
+{
+    fn as_strided(&self) -> Array<StorageType, StridedView> {
+        Array {
+            storage: self.storage.clone(),
+            view: StridedView::from_view(&self.view),
+        }
+    }
 }
 
 impl<T, StorageType, ViewType> Array<StorageType, ViewType>
@@ -95,6 +279,87 @@ where
             .expect("error while getting storage")[offset]
             .clone()
     }
+
+    /// Returns a contiguous array with the same elements. The storage is
+    /// shared when the view is already contiguous, otherwise the elements
+    /// are copied into a new storage.
+    pub fn to_contiguous(&self) -> Array<StorageType, ContiguousView> {
+        if self.is_contiguous() {
+            Array {
+                storage: self.storage.clone(),
+                view: ContiguousView::new_with_offset(self.shape(), self.storage_offset()),
+            }
+        } else {
+            Array {
+                storage: StorageType::from(self.to_vec()),
+                view: ContiguousView::new(self.shape()),
+            }
+        }
+    }
+
+    /// Copies the elements of the array into a vector in row-major order.
+    pub(crate) fn to_vec(&self) -> Vec<T> {
+        let storage = self
+            .storage
+            .storage_get()
+            .expect("error while getting storage");
+        self.view
+            .translate_iter()
+            .map(|i| storage[i].clone())
+            .collect()
+    }
+
+    /// Writes `value` to the element at `idx`. The storage is shared, so the
+    /// write is visible through every array viewing the same element.
+    pub fn set<ListType: AsRef<[usize]>>(&mut self, idx: ListType, value: T) {
+        let offset = self.view.checked_translate(&idx).unwrap_or_else(|| {
+            panic!(
+                "Index {:?} is not valid for a view with shape {:?}",
+                idx.as_ref(),
+                self.shape()
+            )
+        });
+        let mut storage = self
+            .storage
+            .storage_get_mut()
+            .expect("error while getting storage");
+        storage[offset] = value;
+    }
+
+    /// Writes `value` to every element of the view.
+    pub fn fill(&mut self, value: T) {
+        update_values(self, std::iter::repeat(value), |_, value| value);
+    }
+
+    /// Writes the elements of `other`, broadcast to the shape of `self`, into
+    /// the view. `other` may share storage with `self`, in which case it is
+    /// read in full before anything is written.
+    pub fn assign<OtherView: ArrayView>(&mut self, other: &Array<StorageType, OtherView>) {
+        assign_values(self, other, |_, value| value);
+    }
+
+    /// Gives a new shape to the array. If the array is not contiguous, its
+    /// elements are copied into a new storage first.
+    pub fn reshape<SizeType, ListType>(&self, shape: ListType) -> Array<StorageType, ContiguousView>
+    where
+        SizeType: Copy,
+        ShapeDim: From<SizeType>,
+        ListType: AsRef<[SizeType]>,
+    {
+        let new_shape = infer_shape(shape, self.numel()).expect("error while inferring shape");
+        if new_shape.iter().product::<usize>() != self.numel() {
+            panic!(
+                "{:?} not a valid shape for array of size {}",
+                new_shape,
+                self.numel()
+            );
+        }
+        let contiguous = self.to_contiguous();
+        Array {
+            view: ContiguousView::new_with_offset(new_shape, contiguous.storage_offset()),
+            storage: contiguous.storage,
+        }
+    }
 }
 
 impl<T, StorageType> Array<StorageType, ContiguousView>
@@ -110,31 +375,338 @@ where
         Array { storage, view }
     }
 
-    pub fn reshape<SizeType, ListType>(&self, shape: ListType) -> Self
+    /// Creates an array of the given shape filled with ones.
+    pub fn ones<ListType: AsRef<[usize]>>(shape: ListType) -> Self {
+        let view = ContiguousView::new(shape);
+        let mut v = Vec::new();
+        v.resize_with(view.numel(), T::one);
+        let storage = StorageType::from(v);
+        Array { storage, view }
+    }
+
+    /// Creates an array of zeros with the same shape as `other`.
+    pub fn zeros_like<OtherStorage, OtherView>(other: &Array<OtherStorage, OtherView>) -> Self
     where
-        SizeType: Copy,
-        ShapeDim: From<SizeType>,
-        ListType: AsRef<[SizeType]>,
+        OtherStorage: Storage,
+        OtherStorage::Stored: Num,
+        OtherView: ArrayView,
     {
-        let new_shape = infer_shape(shape, self.numel()).expect("error while inferring shape");
-        if new_shape.iter().product::<usize>() != self.numel() {
-            panic!(
-                "{:?} not a valid shape for array of size {}",
-                new_shape,
-                self.numel()
-            );
+        Self::zeros(other.shape())
+    }
+
+    /// Creates an array of ones with the same shape as `other`.
+    pub fn ones_like<OtherStorage, OtherView>(other: &Array<OtherStorage, OtherView>) -> Self
+    where
+        OtherStorage: Storage,
+        OtherStorage::Stored: Num,
+        OtherView: ArrayView,
+    {
+        Self::ones(other.shape())
+    }
+
+    /// Creates an `n x m` matrix with ones on the `k`-th diagonal and zeros
+    /// elsewhere. `m` defaults to `n`, and positive `k` refers to diagonals
+    /// above the main diagonal.
+    pub fn eye(n: usize, m: Option<usize>, k: isize) -> Self {
+        let m = m.unwrap_or(n);
+        let view = ContiguousView::new([n, m]);
+        let mut v = Vec::new();
+        v.resize_with(view.numel(), T::zero);
+        for i in 0..n {
+            let j = i as isize + k;
+            if j >= 0 && (j as usize) < m {
+                v[i * m + j as usize] = T::one();
+            }
         }
-        let view = ContiguousView::new_with_offset(new_shape, self.storage_offset());
-        Array {
-            storage: self.storage.clone(),
-            view,
+        let storage = StorageType::from(v);
+        Array { storage, view }
+    }
+
+    /// Creates the `n x n` identity matrix.
+    pub fn identity(n: usize) -> Self {
+        Self::eye(n, None, 0)
+    }
+}
+
+impl<T, StorageType> Array<StorageType, ContiguousView>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    /// Creates an array of the given shape filled with `value`.
+    pub fn full<ListType: AsRef<[usize]>>(shape: ListType, value: T) -> Self {
+        let view = ContiguousView::new(shape);
+        let storage = StorageType::from(vec![value; view.numel()]);
+        Array { storage, view }
+    }
+
+    /// Creates an array filled with `value` with the same shape as `other`.
+    pub fn full_like<OtherStorage, OtherView>(
+        other: &Array<OtherStorage, OtherView>,
+        value: T,
+    ) -> Self
+    where
+        OtherStorage: Storage,
+        OtherStorage::Stored: Num,
+        OtherView: ArrayView,
+    {
+        Self::full(other.shape(), value)
+    }
+}
+
+impl<T, StorageType> Array<StorageType, ContiguousView>
+where
+    T: Num + NumCast + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    /// Creates a 1-D array of the values `start, start + step, ...` up to but
+    /// excluding `stop`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn arange(start: T, stop: T, step: T) -> Self {
+        if step.is_zero() {
+            panic!("arange: step must be non-zero");
+        }
+        // The length is worked out in f64, as `stop - start` can overflow
+        // narrow integer types even when every value of the range fits.
+        let to_f64 = |x: &T| x.to_f64().unwrap();
+        let span = (to_f64(&stop) - to_f64(&start)) / to_f64(&step);
+        let len = if span > 0.0 { span.ceil() as usize } else { 0 };
+        // Whole-number ranges are evaluated in i128 so that `step * i` cannot
+        // overflow either. Anything else uses `start + step * i` rather than
+        // a running sum, which would accumulate rounding error.
+        let as_integer = |x: &T| x.to_i128().filter(|&n| T::from(n).as_ref() == Some(x));
+        let v = match (as_integer(&start), as_integer(&step)) {
+            (Some(start), Some(step)) => (0..len as i128)
+                .map(|i| T::from(start + step * i).unwrap())
+                .collect::<Vec<T>>(),
+            _ => (0..len)
+                .map(|i| start.clone() + step.clone() * T::from(i).unwrap())
+                .collect::<Vec<T>>(),
+        };
+        let view = ContiguousView::new([len]);
+        let storage = StorageType::from(v);
+        Array { storage, view }
+    }
+}
+
+impl<T, StorageType> Array<StorageType, ContiguousView>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+{
+    /// Creates a 1-D array of `num` evenly spaced values from `start` to
+    /// `stop`. With `endpoint`, `stop` is the last value; otherwise it is
+    /// excluded.
+    pub fn linspace(start: T, stop: T, num: usize, endpoint: bool) -> Self {
+        let divisions = if endpoint { num.saturating_sub(1) } else { num };
+        let step = if divisions > 0 {
+            (stop - start) / T::from(divisions).unwrap()
+        } else {
+            T::zero()
+        };
+        let mut v = (0..num)
+            .map(|i| start + step * T::from(i).unwrap())
+            .collect::<Vec<T>>();
+        if endpoint && num > 1 {
+            v[num - 1] = stop;
+        }
+        let view = ContiguousView::new([num]);
+        let storage = StorageType::from(v);
+        Array { storage, view }
+    }
+
+    /// Creates a 1-D array of `num` values spaced evenly on a log scale, from
+    /// `base^start` to `base^stop`.
+    pub fn logspace(start: T, stop: T, num: usize, endpoint: bool, base: T) -> Self {
+        let exponents = Self::linspace(start, stop, num, endpoint);
+        let v = exponents
+            .to_vec()
+            .into_iter()
+            .map(|exponent| base.powf(exponent))
+            .collect::<Vec<T>>();
+        let view = ContiguousView::new([num]);
+        let storage = StorageType::from(v);
+        Array { storage, view }
+    }
+
+    /// Creates a 1-D array of `num` values forming a geometric progression
+    /// from `start` to `stop`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` or `stop` is zero, or if they differ in sign.
+    pub fn geomspace(start: T, stop: T, num: usize, endpoint: bool) -> Self {
+        if start.is_zero() || stop.is_zero() || start.signum() != stop.signum() {
+            panic!("geomspace: start and stop must be non-zero and of the same sign");
+        }
+        let sign = start.signum();
+        let exponents = Self::linspace(start.abs().ln(), stop.abs().ln(), num, endpoint);
+        let mut v = exponents
+            .to_vec()
+            .into_iter()
+            .map(|exponent| sign * exponent.exp())
+            .collect::<Vec<T>>();
+        if num > 0 {
+            v[0] = start;
+        }
+        if endpoint && num > 1 {
+            v[num - 1] = stop;
+        }
+        let view = ContiguousView::new([num]);
+        let storage = StorageType::from(v);
+        Array { storage, view }
+    }
+}
+
+impl<T, StorageType> Array<StorageType, ContiguousView>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+{
+    /// Creates an array of the given shape from elements in row-major order.
+    pub fn from_shape_vec<ListType: AsRef<[usize]>>(
+        shape: ListType,
+        data: Vec<T>,
+    ) -> Result<Self, String> {
+        let view = ContiguousView::new(shape);
+        if view.numel() != data.len() {
+            return Err(format!(
+                "cannot create an array of shape {:?} from {} elements",
+                view.shape(),
+                data.len()
+            ));
         }
+        let storage = StorageType::from(data);
+        Ok(Array { storage, view })
+    }
+}
+
+/// Collects the elements into a 1-D array, which can then be given its final
+/// shape with [`Array::reshape`].
+impl<T, StorageType> FromIterator<T> for Array<StorageType, ContiguousView>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let data = iter.into_iter().collect::<Vec<T>>();
+        let view = ContiguousView::new([data.len()]);
+        let storage = StorageType::from(data);
+        Array { storage, view }
     }
 }
 
+// Conversions from nested containers are implemented per scalar type, a
+// blanket implementation over `T` would overlap with the nested ones.
+macro_rules! impl_conversions {
+    ($($scalar:ty),*) => {
+        $(
+            impl<StorageType> From<Vec<$scalar>> for Array<StorageType, ContiguousView>
+            where
+                StorageType: Storage<Stored = $scalar>,
+            {
+                fn from(data: Vec<$scalar>) -> Self {
+                    data.into_iter().collect()
+                }
+            }
+
+            /// Builds a 2-D array from a list of rows, which must all have
+            /// the same length.
+            impl<StorageType> TryFrom<Vec<Vec<$scalar>>> for Array<StorageType, ContiguousView>
+            where
+                StorageType: Storage<Stored = $scalar>,
+            {
+                type Error = String;
+
+                fn try_from(rows: Vec<Vec<$scalar>>) -> Result<Self, Self::Error> {
+                    let cols = rows.first().map_or(0, |row| row.len());
+                    if let Some((idx, row)) =
+                        rows.iter().enumerate().find(|(_, row)| row.len() != cols)
+                    {
+                        return Err(format!(
+                            "ragged rows: row {} has {} elements, but row 0 has {}",
+                            idx,
+                            row.len(),
+                            cols
+                        ));
+                    }
+                    let shape = [rows.len(), cols];
+                    Self::from_shape_vec(shape, rows.into_iter().flatten().collect())
+                }
+            }
+
+            impl<StorageType, const N: usize> From<[$scalar; N]>
+                for Array<StorageType, ContiguousView>
+            where
+                StorageType: Storage<Stored = $scalar>,
+            {
+                fn from(data: [$scalar; N]) -> Self {
+                    Self::from(Vec::from(data))
+                }
+            }
+
+            impl<StorageType, const M: usize, const N: usize> From<[[$scalar; N]; M]>
+                for Array<StorageType, ContiguousView>
+            where
+                StorageType: Storage<Stored = $scalar>,
+            {
+                fn from(data: [[$scalar; N]; M]) -> Self {
+                    let data = Vec::from(data).into_iter().flat_map(Vec::from).collect();
+                    let view = ContiguousView::new([M, N]);
+                    let storage = StorageType::from(data);
+                    Array { storage, view }
+                }
+            }
+
+            impl<StorageType, const L: usize, const M: usize, const N: usize>
+                From<[[[$scalar; N]; M]; L]> for Array<StorageType, ContiguousView>
+            where
+                StorageType: Storage<Stored = $scalar>,
+            {
+                fn from(data: [[[$scalar; N]; M]; L]) -> Self {
+                    let data = Vec::from(data)
+                        .into_iter()
+                        .flat_map(Vec::from)
+                        .flat_map(Vec::from)
+                        .collect();
+                    let view = ContiguousView::new([L, M, N]);
+                    let storage = StorageType::from(data);
+                    Array { storage, view }
+                }
+            }
+        )*
+    };
+}
+
+impl_conversions!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// Builds an array from a nested list literal with up to three dimensions,
+/// such as `array![[1, 2], [3, 4]]`. Every row must have the same length,
+/// ragged input does not compile.
+///
+/// ```
+/// use mathoxide_lib::array::Array;
+/// use mathoxide_lib::{array, ContiguousView, ThreadSafeStorage};
+///
+/// let a: Array<ThreadSafeStorage<f64>, ContiguousView> = array![[1.0, 2.0], [3.0, 4.0]];
+/// assert_eq!(a.shape(), &[2, 2]);
+/// ```
+#[macro_export]
+macro_rules! array {
+    ($($elem:expr),* $(,)?) => {
+        $crate::array::Array::from([$($elem),*])
+    };
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::s;
+    use crate::slice::NewAxis;
+    use crate::test_utils::arange;
     use crate::thread_safe_storage::ThreadSafeStorage;
 
     #[test]
@@ -146,6 +718,192 @@ mod test {
         }
     }
 
+    #[test]
+    fn ones_full_and_like() {
+        let ones = Array::<ThreadSafeStorage<u32>, ContiguousView>::ones([2, 3]);
+        assert_eq!(ones.to_vec(), vec![1; 6]);
+        let full = Array::<ThreadSafeStorage<f32>, ContiguousView>::full([3], 2.5);
+        assert_eq!(full.to_vec(), vec![2.5; 3]);
+
+        let other = arange(&[2, 3]).t();
+        let zeros = Array::<ThreadSafeStorage<f64>, ContiguousView>::zeros_like(&other);
+        assert_eq!(zeros.shape(), &[3, 2]);
+        assert_eq!(zeros.to_vec(), vec![0.0; 6]);
+        let ones = Array::<ThreadSafeStorage<i8>, ContiguousView>::ones_like(&other);
+        assert_eq!(ones.to_vec(), vec![1; 6]);
+        let full = Array::<ThreadSafeStorage<i8>, ContiguousView>::full_like(&other, -3);
+        assert_eq!(full.shape(), &[3, 2]);
+        assert_eq!(full.to_vec(), vec![-3; 6]);
+    }
+
+    #[test]
+    fn eye_and_identity() {
+        type Matrix = Array<ThreadSafeStorage<u32>, ContiguousView>;
+        assert_eq!(Matrix::identity(2).to_vec(), vec![1, 0, 0, 1]);
+        let upper = Matrix::eye(2, Some(3), 1);
+        assert_eq!(upper.shape(), &[2, 3]);
+        assert_eq!(upper.to_vec(), vec![0, 1, 0, 0, 0, 1]);
+        let lower = Matrix::eye(3, Some(2), -1);
+        assert_eq!(lower.to_vec(), vec![0, 0, 1, 0, 0, 1]);
+        assert_eq!(Matrix::eye(2, None, 5).to_vec(), vec![0; 4]);
+    }
+
+    #[test]
+    fn arange_ranges() {
+        type Ints = Array<ThreadSafeStorage<i32>, ContiguousView>;
+        assert_eq!(Ints::arange(0, 5, 2).to_vec(), vec![0, 2, 4]);
+        assert_eq!(Ints::arange(5, 0, -2).to_vec(), vec![5, 3, 1]);
+        assert_eq!(Ints::arange(5, 0, 1).shape(), &[0]);
+        let unsigned = Array::<ThreadSafeStorage<u32>, ContiguousView>::arange(5, 0, 1);
+        assert_eq!(unsigned.shape(), &[0]);
+        let unsigned = Array::<ThreadSafeStorage<u8>, ContiguousView>::arange(0, 255, 1);
+        assert_eq!(unsigned.to_vec(), (0..255).collect::<Vec<u8>>());
+        type Bytes = Array<ThreadSafeStorage<i8>, ContiguousView>;
+        assert_eq!(
+            Bytes::arange(-128, 127, 1).to_vec(),
+            (-128..127).collect::<Vec<i8>>()
+        );
+        let descending = Bytes::arange(100, -100, -1).to_vec();
+        assert_eq!(descending.len(), 200);
+        assert_eq!((descending[0], descending[199]), (100, -99));
+        let floats = Array::<ThreadSafeStorage<f64>, ContiguousView>::arange(0.0, 1.0, 0.25);
+        assert_eq!(floats.to_vec(), vec![0.0, 0.25, 0.5, 0.75]);
+        let tenths = Array::<ThreadSafeStorage<f64>, ContiguousView>::arange(0.0, 1.0, 0.1);
+        assert_eq!(tenths.shape(), &[10]);
+        assert_eq!((tenths.get([8]).item(), tenths.get([9]).item()), (0.8, 0.9));
+        let long = Array::<ThreadSafeStorage<f64>, ContiguousView>::arange(0.0, 1e5, 0.1).to_vec();
+        assert_eq!(long.len(), 1_000_000);
+        assert_eq!(long[999_999], 0.1 * 999_999.0);
+        assert!(std::panic::catch_unwind(|| Ints::arange(0, 5, 0)).is_err());
+    }
+
+    #[test]
+    fn spaced_ranges() {
+        type Floats = Array<ThreadSafeStorage<f64>, ContiguousView>;
+        assert_eq!(
+            Floats::linspace(0.0, 1.0, 5, true).to_vec(),
+            vec![0.0, 0.25, 0.5, 0.75, 1.0]
+        );
+        assert_eq!(
+            Floats::linspace(0.0, 1.0, 4, false).to_vec(),
+            vec![0.0, 0.25, 0.5, 0.75]
+        );
+        assert_eq!(Floats::linspace(3.0, 7.0, 1, true).to_vec(), vec![3.0]);
+        assert_eq!(Floats::linspace(3.0, 7.0, 0, true).shape(), &[0]);
+
+        let logs = Floats::logspace(0.0, 3.0, 4, true, 10.0).to_vec();
+        for (x, y) in logs.iter().zip([1.0, 10.0, 100.0, 1000.0].iter()) {
+            assert!((x - y).abs() <= 1e-12 * y);
+        }
+        let geoms = Floats::geomspace(-1.0, -16.0, 5, true).to_vec();
+        for (x, y) in geoms.iter().zip([-1.0, -2.0, -4.0, -8.0, -16.0].iter()) {
+            assert!((x - y).abs() <= 1e-12 * y.abs());
+        }
+        assert_eq!(geoms[4], -16.0);
+        assert!(std::panic::catch_unwind(|| Floats::geomspace(0.0, 1.0, 3, true)).is_err());
+        assert!(std::panic::catch_unwind(|| Floats::geomspace(-1.0, 1.0, 3, true)).is_err());
+    }
+
+    #[test]
+    fn from_shape_vec_checks_length() {
+        type Matrix = Array<ThreadSafeStorage<u32>, ContiguousView>;
+        let a = Matrix::from_shape_vec([2, 3], vec![0, 1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(a.shape(), &[2, 3]);
+        assert_eq!(a.get([1, 0]).item(), 3);
+        let err = Matrix::from_shape_vec([2, 3], vec![0; 5]).err().unwrap();
+        assert!(err.contains("[2, 3]"), "{}", err);
+    }
+
+    #[test]
+    fn from_nested_vecs() {
+        type Matrix = Array<ThreadSafeStorage<i32>, ContiguousView>;
+        let a = Matrix::try_from(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!(a.shape(), &[2, 3]);
+        assert_eq!(a.to_vec(), vec![1, 2, 3, 4, 5, 6]);
+        let err = Matrix::try_from(vec![vec![1, 2], vec![3]]).err().unwrap();
+        assert!(err.contains("row 1"), "{}", err);
+        assert_eq!(
+            Matrix::try_from(Vec::<Vec<i32>>::new()).unwrap().shape(),
+            &[0, 0]
+        );
+        assert_eq!(Matrix::from(vec![7, 8]).shape(), &[2]);
+    }
+
+    #[test]
+    fn collect_and_reshape() {
+        let a = (0..6u32)
+            .map(|x| x * 2)
+            .collect::<Array<ThreadSafeStorage<u32>, ContiguousView>>();
+        assert_eq!(a.shape(), &[6]);
+        let a = a.reshape([3usize, 2]);
+        assert_eq!(a.get([2, 1]).item(), 10);
+    }
+
+    #[test]
+    fn array_macro() {
+        let a: Array<ThreadSafeStorage<i64>, ContiguousView> = crate::array![[1, 2], [3, 4]];
+        assert_eq!(a.shape(), &[2, 2]);
+        assert_eq!(a.to_vec(), vec![1, 2, 3, 4]);
+        let v: Array<ThreadSafeStorage<f64>, ContiguousView> = crate::array![1.0, 2.0, 3.0];
+        assert_eq!(v.shape(), &[3]);
+        let c: Array<ThreadSafeStorage<u8>, ContiguousView> =
+            crate::array![[[1, 2, 3], [4, 5, 6]], [[7, 8, 9], [10, 11, 12]]];
+        assert_eq!(c.shape(), &[2, 2, 3]);
+        assert_eq!(c.get([1, 0, 2]).item(), 9);
+    }
+
+    #[test]
+    fn set_through_slice_is_visible_to_parent() {
+        let parent = arange(&[3, 4]);
+        let mut block = parent.slice(s![1.., ..;2]);
+        block.set([1, 1], 100);
+        assert_eq!(parent.get([2, 2]).item(), 100);
+
+        let mut transposed = parent.t();
+        transposed.set([3, 0], 42);
+        assert_eq!(parent.get([0, 3]).item(), 42);
+        assert!(std::panic::catch_unwind(move || block.set([2, 0], 1)).is_err());
+    }
+
+    #[test]
+    fn fill_sliced_view() {
+        let parent = arange(&[2, 3]);
+        let mut column = parent.slice(s![.., 1]);
+        column.fill(7);
+        assert_eq!(parent.to_vec(), vec![0, 7, 2, 3, 7, 5]);
+
+        let mut broadcast = arange(&[3]).broadcast_to([2, 3]);
+        assert!(std::panic::catch_unwind(move || broadcast.fill(0)).is_err());
+    }
+
+    #[test]
+    fn assign_broadcasts_into_view() {
+        let parent = arange(&[3, 3]);
+        let mut block = parent.slice(s![1.., 1..]);
+        let row = Array::from_parts(
+            ThreadSafeStorage::from(vec![10i64, 20]),
+            ContiguousView::new([2]),
+        );
+        block.assign(&row);
+        assert_eq!(parent.to_vec(), vec![0, 1, 2, 3, 10, 20, 6, 10, 20]);
+
+        let mut wrong = parent.slice(s![.., ..2]);
+        let other = arange(&[3]);
+        assert!(std::panic::catch_unwind(move || wrong.assign(&other)).is_err());
+    }
+
+    #[test]
+    fn assign_from_overlapping_view() {
+        let mut parent = arange(&[4]);
+        let shifted = parent.slice(s![..3]);
+        parent.slice(s![1..]).assign(&shifted);
+        assert_eq!(parent.to_vec(), vec![0, 0, 1, 2]);
+
+        let reversed = parent.slice(s![..;-1]);
+        parent.assign(&reversed);
+        assert_eq!(parent.to_vec(), vec![2, 1, 0, 0]);
+    }
+
     #[test]
     fn item_panic() {
         let array = Array::<ThreadSafeStorage<u32>, ContiguousView>::zeros(&[4, 5]);
@@ -180,4 +938,125 @@ mod test {
         let row_vec = array.reshape([-1isize]);
         assert_eq!(row_vec.shape(), &[6]);
     }
+
+    #[test]
+    fn transpose_2d() {
+        let array = arange(&[2, 3]);
+        let transposed = array.t();
+        assert_eq!(transposed.shape(), &[3, 2]);
+        assert_eq!(transposed.stride(), &[1, 3]);
+        assert!(!transposed.is_contiguous());
+        assert_eq!(transposed.to_vec(), vec![0, 3, 1, 4, 2, 5]);
+        assert_eq!(transposed.get([2, 1]).item(), 5);
+    }
+
+    #[test]
+    fn transpose_shares_storage() {
+        let mut array = arange(&[2, 3]);
+        let transposed = array.transpose();
+        array.storage.storage_get_mut().unwrap()[1] = 100;
+        assert_eq!(transposed.get([1, 0]).item(), 100);
+        assert_eq!(transposed.storage_size(), array.storage_size());
+    }
+
+    #[test]
+    fn permute_axes_3d() {
+        let array = arange(&[2, 3, 4]);
+        let permuted = array.permute_axes([1, 2, 0]);
+        assert_eq!(permuted.shape(), &[3, 4, 2]);
+        assert_eq!(permuted.get([2, 1, 1]).item(), array.get([1, 2, 1]).item());
+    }
+
+    #[test]
+    fn permute_axes_panic() {
+        let array = arange(&[2, 3]);
+        let result = std::panic::catch_unwind(|| array.permute_axes([0, 0]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn reshape_transposed_copies() {
+        let mut array = arange(&[2, 3]);
+        let reshaped = array.t().reshape([-1isize]);
+        assert_eq!(reshaped.to_vec(), vec![0, 3, 1, 4, 2, 5]);
+        array.storage.storage_get_mut().unwrap()[1] = 100;
+        assert_eq!(reshaped.get([2]).item(), 1);
+    }
+
+    #[test]
+    fn reshape_contiguous_shares_storage() {
+        let mut array = arange(&[2, 3]);
+        let reshaped = array.t().t().reshape([3usize, 2]);
+        array.storage.storage_get_mut().unwrap()[5] = 100;
+        assert_eq!(reshaped.get([2, 1]).item(), 100);
+    }
+
+    #[test]
+    fn slice_sub_block() {
+        let array = arange(&[4, 5]);
+        let block = array.slice(s![1..3, 1..;2]);
+        assert_eq!(block.shape(), &[2, 2]);
+        assert_eq!(block.to_vec(), vec![6, 8, 11, 13]);
+    }
+
+    #[test]
+    fn slice_drops_indexed_axis() {
+        let array = arange(&[2, 3, 4]);
+        let row = array.slice(s![1, .., -1]);
+        assert_eq!(row.shape(), &[3]);
+        assert_eq!(row.to_vec(), vec![15, 19, 23]);
+    }
+
+    #[test]
+    fn slice_shares_storage() {
+        let mut array = arange(&[4, 5]);
+        let reversed = array.slice(s![..;-1, 2]);
+        array.storage.storage_get_mut().unwrap()[2] = 100;
+        assert_eq!(reversed.to_vec(), vec![17, 12, 7, 100]);
+    }
+
+    #[test]
+    fn slice_of_transpose() {
+        let array = arange(&[2, 3]);
+        let sliced = array.t().slice(s![1.., NewAxis, ..]);
+        assert_eq!(sliced.shape(), &[2, 1, 2]);
+        assert_eq!(sliced.to_vec(), vec![1, 4, 2, 5]);
+    }
+
+    #[test]
+    fn slice_panic() {
+        let array = arange(&[2, 3]);
+        let result = std::panic::catch_unwind(|| array.slice(s![.., 3]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn broadcast_row() {
+        let array = arange(&[3]);
+        let broadcast = array.broadcast_to([2, 3]);
+        assert_eq!(broadcast.shape(), &[2, 3]);
+        assert_eq!(broadcast.stride(), &[0, 1]);
+        assert_eq!(broadcast.to_vec(), vec![0, 1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn broadcast_column_of_transpose() {
+        let array = arange(&[1, 3]);
+        let broadcast = array.t().broadcast_to([2, 3, 2]);
+        assert_eq!(broadcast.to_vec(), vec![0, 0, 1, 1, 2, 2, 0, 0, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn broadcast_panic() {
+        let array = arange(&[2, 3]);
+        let result = std::panic::catch_unwind(|| array.broadcast_to([3, 3]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn format_transposed() {
+        let array = arange(&[2, 3]);
+        assert_eq!(array.t().to_string(), "[[0,3]\n [1,4]\n [2,5]]");
+        assert_eq!(array.t().reshape([-1isize]).to_string(), "[0,3,1,4,2,5]");
+    }
 }
@@ -0,0 +1,403 @@
+use std::marker::PhantomData;
+
+use num_traits::Num;
+
+use crate::array::Array;
+use crate::storage::Storage;
+use crate::utils::IndexIteration;
+use crate::view_iters::StridedViewIterator;
+use crate::views::{ArrayView, StridedView};
+
+/// Iterator over references to the elements of an array in row-major order,
+/// returned by [`Array::iter`].
+///
+/// Holds a read guard on the storage until it is dropped.
+///
+/// ```
+/// use mathoxide_lib::array::Array;
+/// use mathoxide_lib::{array, ContiguousView, ThreadSafeStorage};
+///
+/// let a: Array<ThreadSafeStorage<f64>, ContiguousView> = array![[1.0, 2.0], [3.0, 4.0]];
+/// assert_eq!(a.iter().sum::<f64>(), 10.0);
+/// let columns = a.t().iter().copied().collect::<Vec<f64>>();
+/// assert_eq!(columns, vec![1.0, 3.0, 2.0, 4.0]);
+/// ```
+///
+/// [`Array::iter`]: crate::array::Array::iter
+pub struct Iter<'a, StorageType: Storage + 'a, ViewType: ArrayView> {
+    _guard: StorageType::Guard<'a>,
+    data: *const [StorageType::Stored],
+    offsets: ViewType::IterType,
+}
+
+impl<'a, StorageType: Storage + 'a, ViewType: ArrayView> Iter<'a, StorageType, ViewType> {
+    pub(crate) fn new(storage: &'a StorageType, view: &'a ViewType) -> Self {
+        let guard = storage.storage_get().expect("error while getting storage");
+        Self {
+            data: &*guard,
+            _guard: guard,
+            offsets: view.translate_iter(),
+        }
+    }
+}
+
+impl<'a, StorageType, ViewType> Iterator for Iter<'a, StorageType, ViewType>
+where
+    StorageType: Storage + 'a,
+    ViewType: ArrayView,
+{
+    type Item = &'a StorageType::Stored;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offsets.next()?;
+        // SAFETY: `data` points into the storage, which is borrowed for 'a
+        // and never reallocated. The guard keeps writers out while the
+        // iterator lives.
+        Some(unsafe { &(*self.data)[offset] })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+/// Iterator over the row-major indices of and references to the elements of
+/// an array, returned by [`Array::indexed_iter`].
+///
+/// Like [`Iter`], it holds a read guard on the storage until it is dropped.
+///
+/// [`Array::indexed_iter`]: crate::array::Array::indexed_iter
+pub struct IndexedIter<'a, StorageType: Storage + 'a, ViewType: ArrayView> {
+    _guard: StorageType::Guard<'a>,
+    data: *const [StorageType::Stored],
+    offsets: ViewType::IterType,
+    index: IndexIteration<'a>,
+}
+
+impl<'a, StorageType: Storage + 'a, ViewType: ArrayView> IndexedIter<'a, StorageType, ViewType> {
+    pub(crate) fn new(storage: &'a StorageType, view: &'a ViewType) -> Self {
+        let guard = storage.storage_get().expect("error while getting storage");
+        Self {
+            data: &*guard,
+            _guard: guard,
+            offsets: view.translate_iter(),
+            index: IndexIteration::row_major(view.shape()),
+        }
+    }
+}
+
+impl<'a, StorageType, ViewType> Iterator for IndexedIter<'a, StorageType, ViewType>
+where
+    StorageType: Storage + 'a,
+    ViewType: ArrayView,
+{
+    type Item = (Vec<usize>, &'a StorageType::Stored);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The offsets run out first for empty arrays, which still have a
+        // first index.
+        let offset = self.offsets.next()?;
+        let index = self.index.next()?.to_vec();
+        // SAFETY: as in `Iter::next`.
+        Some((index, unsafe { &(*self.data)[offset] }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+/// Iterator over mutable references to the elements of an array in
+/// row-major order, returned by [`Array::iter_mut`].
+///
+/// Holds a write guard on the storage until it is dropped.
+///
+/// [`Array::iter_mut`]: crate::array::Array::iter_mut
+pub struct IterMut<'a, StorageType: Storage + 'a, ViewType: ArrayView> {
+    _guard: StorageType::GuardMut<'a>,
+    data: *mut [StorageType::Stored],
+    offsets: ViewType::IterType,
+    marker: PhantomData<&'a mut StorageType::Stored>,
+}
+
+impl<'a, StorageType: Storage + 'a, ViewType: ArrayView> IterMut<'a, StorageType, ViewType> {
+    /// `view` must not map two indices to the same element.
+    pub(crate) fn new(storage: &'a mut StorageType, view: &'a ViewType) -> Self {
+        let mut guard = storage
+            .storage_get_mut()
+            .expect("error while getting storage");
+        Self {
+            data: &mut *guard,
+            _guard: guard,
+            offsets: view.translate_iter(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, StorageType, ViewType> Iterator for IterMut<'a, StorageType, ViewType>
+where
+    StorageType: Storage + 'a,
+    ViewType: ArrayView,
+{
+    type Item = &'a mut StorageType::Stored;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offsets.next()?;
+        // SAFETY: `data` points into the storage, which is borrowed mutably
+        // for 'a and never reallocated. The view maps every index to a
+        // distinct offset, so no element is handed out twice.
+        Some(unsafe { &mut (*self.data)[offset] })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+/// Views into an array that share its storage, returned by
+/// [`Array::axis_iter`], [`Array::outer_iter`] and [`Array::lanes`].
+///
+/// Unlike the element iterators, no guard is held: every view is a new
+/// handle on the storage, which is only locked when the view is read or
+/// written.
+///
+/// [`Array::axis_iter`]: crate::array::Array::axis_iter
+/// [`Array::outer_iter`]: crate::array::Array::outer_iter
+/// [`Array::lanes`]: crate::array::Array::lanes
+pub struct AxisIter<StorageType> {
+    storage: StorageType,
+    shape: Vec<usize>,
+    stride: Vec<usize>,
+    offsets: StridedViewIterator,
+}
+
+impl<StorageType> AxisIter<StorageType> {
+    /// Yields a view with the given `shape` and `stride` at every offset of
+    /// `outer`.
+    pub(crate) fn new(
+        storage: StorageType,
+        outer: &StridedView,
+        shape: &[usize],
+        stride: &[usize],
+    ) -> Self {
+        Self {
+            storage,
+            shape: shape.to_vec(),
+            stride: stride.to_vec(),
+            offsets: outer.translate_iter(),
+        }
+    }
+}
+
+impl<T, StorageType> Iterator for AxisIter<StorageType>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+{
+    type Item = Array<StorageType, StridedView>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.offsets.next().map(|offset| {
+            Array::from_parts(
+                self.storage.clone(),
+                StridedView::new_with_offset(&self.shape, &self.stride, offset),
+            )
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+/// Like [`AxisIter`], returned by the `_mut` variants. The only difference is
+/// that those check up front that the parent is not a broadcast view. The
+/// views own a handle on the storage and outlive the borrow of the parent,
+/// and views from [`AxisIter`] can be written through just the same.
+pub struct AxisIterMut<'a, StorageType> {
+    views: AxisIter<StorageType>,
+    marker: PhantomData<&'a mut StorageType>,
+}
+
+impl<'a, StorageType> AxisIterMut<'a, StorageType> {
+    pub(crate) fn new(views: AxisIter<StorageType>) -> Self {
+        Self {
+            views,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, StorageType> Iterator for AxisIterMut<'a, StorageType>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+{
+    type Item = Array<StorageType, StridedView>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.views.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.views.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::array::Array;
+    use crate::s;
+    use crate::test_utils::arange;
+    use crate::thread_safe_storage::ThreadSafeStorage;
+    use crate::thread_unsafe_storage::ThreadUnsafeStorage;
+    use crate::views::ContiguousView;
+
+    #[test]
+    fn iter_contiguous_and_strided() {
+        let a = arange(&[2, 3]);
+        let values = a.iter().copied().collect::<Vec<i64>>();
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 5]);
+        let values = a.t().iter().copied().collect::<Vec<i64>>();
+        assert_eq!(values, vec![0, 3, 1, 4, 2, 5]);
+        let sliced = a.slice(s![.., ..;-2]);
+        assert_eq!(sliced.iter().sum::<i64>(), 10);
+
+        assert_eq!(a.iter().count(), 6);
+        assert_eq!(a.iter().max(), Some(&5));
+    }
+
+    #[test]
+    fn iter_empty_and_scalar() {
+        let empty = arange(&[2, 0]);
+        assert_eq!(empty.iter().count(), 0);
+        assert_eq!(empty.indexed_iter().count(), 0);
+        let scalar = arange(&[]);
+        let items = scalar
+            .indexed_iter()
+            .map(|(index, &value)| (index, value))
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec![(vec![], 0)]);
+    }
+
+    #[test]
+    fn indexed_iter_matches_get() {
+        let a = arange(&[2, 3, 2]);
+        let view = a.permute_axes([2, 0, 1]);
+        let mut count = 0;
+        for (index, &value) in view.indexed_iter() {
+            assert_eq!(view.get(&index).item(), value);
+            count += 1;
+        }
+        assert_eq!(count, 12);
+        let indices = arange(&[2, 2])
+            .indexed_iter()
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            indices,
+            vec![vec![0, 0], vec![0, 1], vec![1, 0], vec![1, 1]]
+        );
+    }
+
+    #[test]
+    fn iter_mut_writes_through_views() {
+        let a = arange(&[3, 4]);
+        let mut column = a.slice(s![.., 1]);
+        for x in column.iter_mut() {
+            *x *= 10;
+        }
+        assert_eq!(a.to_vec(), vec![0, 10, 2, 3, 4, 50, 6, 7, 8, 90, 10, 11]);
+
+        let mut transposed = a.t();
+        for (i, x) in transposed.iter_mut().enumerate() {
+            *x = i as i64;
+        }
+        assert_eq!(a.to_vec(), vec![0, 3, 6, 9, 1, 4, 7, 10, 2, 5, 8, 11]);
+
+        let mut b = Array::<ThreadUnsafeStorage<f64>, ContiguousView>::ones([2, 2]);
+        b.iter_mut().for_each(|x| *x += 1.0);
+        assert_eq!(b.to_vec(), vec![2.0; 4]);
+    }
+
+    #[test]
+    fn axis_iter_yields_sub_arrays() {
+        let a = arange(&[2, 3, 4]);
+        let views = a.axis_iter(1).collect::<Vec<_>>();
+        assert_eq!(views.len(), 3);
+        assert_eq!(views[1].shape(), &[2, 4]);
+        assert_eq!(views[1].to_vec(), a.slice(s![.., 1, ..]).to_vec());
+
+        let rows = a.slice(s![1, ..;-1]).outer_iter().collect::<Vec<_>>();
+        assert_eq!(rows[0].to_vec(), vec![20, 21, 22, 23]);
+        assert_eq!(rows[2].to_vec(), vec![12, 13, 14, 15]);
+        assert_eq!(arange(&[0, 3]).outer_iter().count(), 0);
+    }
+
+    #[test]
+    fn lanes_along_each_axis() {
+        let a = arange(&[2, 3]);
+        let rows = a.lanes(-1).map(|lane| lane.to_vec()).collect::<Vec<_>>();
+        assert_eq!(rows, vec![vec![0, 1, 2], vec![3, 4, 5]]);
+        let columns = a.lanes(0).map(|lane| lane.to_vec()).collect::<Vec<_>>();
+        assert_eq!(columns, vec![vec![0, 3], vec![1, 4], vec![2, 5]]);
+
+        let b = arange(&[2, 3, 2]);
+        let sums = b
+            .lanes(1)
+            .map(|lane| lane.iter().sum::<i64>())
+            .collect::<Vec<i64>>();
+        assert_eq!(sums, vec![6, 9, 24, 27]);
+    }
+
+    #[test]
+    fn mutable_views_write_to_parent() {
+        let mut a = Array::<ThreadSafeStorage<f64>, ContiguousView>::from([
+            [1.0, 3.0],
+            [2.0, 6.0],
+            [5.0, 5.0],
+        ]);
+        for mut row in a.outer_iter_mut() {
+            let total = row.iter().sum::<f64>();
+            for x in row.iter_mut() {
+                *x /= total;
+            }
+        }
+        assert_eq!(a.to_vec(), vec![0.25, 0.75, 0.25, 0.75, 0.5, 0.5]);
+
+        for (i, mut column) in a.lanes_mut(0).enumerate() {
+            column.fill(i as f64);
+        }
+        assert_eq!(a.to_vec(), vec![0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+
+        let mut t = a.t();
+        for mut view in t.axis_iter_mut(1) {
+            view.set([0], -1.0);
+        }
+        assert_eq!(a.slice(s![.., 0]).to_vec(), vec![-1.0; 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn axis_iter_rejects_invalid_axis() {
+        arange(&[2, 3]).axis_iter(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "broadcast view")]
+    fn lanes_mut_rejects_broadcast_views() {
+        let a = arange(&[3]);
+        a.broadcast_to([2, 3]).lanes_mut(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "broadcast view")]
+    fn iter_mut_rejects_broadcast_views() {
+        let a = arange(&[3]);
+        let mut broadcast = a.broadcast_to([2, 3]);
+        for x in &mut broadcast.iter_mut() {
+            *x = 0;
+        }
+    }
+}
@@ -0,0 +1,583 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use num_traits::Num;
+
+use crate::array::{Array, AsStrided};
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView, StridedView};
+
+mod path;
+
+pub use path::{ContractionPath, ContractionStep, Optimize};
+
+/// An axis label in einsum subscripts.
+///
+/// Axes covered by an ellipsis are labelled by their position within the
+/// broadcast ellipsis dimensions, so that they line up from the right across
+/// operands. They sort before named labels, which puts them first in an
+/// implicit output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Label {
+    Broadcast(usize),
+    Named(char),
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Label::Broadcast(axis) => write!(f, "'...' (axis {})", axis),
+            Label::Named(name) => write!(f, "'{}'", name),
+        }
+    }
+}
+
+/// Subscripts of an einsum expression, with ellipses expanded against the
+/// dimensions of the operands.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Subscripts {
+    pub inputs: Vec<Vec<Label>>,
+    pub output: Vec<Label>,
+}
+
+/// A single term of the subscripts, where `None` marks the ellipsis.
+fn parse_term(term: &str) -> Result<Vec<Option<char>>, String> {
+    let mut labels = Vec::new();
+    let mut rest = term;
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_alphabetic() {
+            labels.push(Some(c));
+            rest = &rest[1..];
+        } else if let Some(stripped) = rest.strip_prefix("...") {
+            if labels.contains(&None) {
+                return Err(format!(
+                    "einsum: subscripts '{}' contain more than one ellipsis",
+                    term
+                ));
+            }
+            labels.push(None);
+            rest = stripped;
+        } else {
+            return Err(format!(
+                "einsum: invalid character '{}' in subscripts '{}'",
+                c, term
+            ));
+        }
+    }
+    Ok(labels)
+}
+
+impl Subscripts {
+    /// Parses `subscripts` for operands with the given numbers of dimensions.
+    pub fn parse(subscripts: &str, ndims: &[usize]) -> Result<Self, String> {
+        let subscripts = subscripts
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        let (inputs, output) = match subscripts.split_once("->") {
+            Some((inputs, output)) => (inputs, Some(output)),
+            None => (&subscripts[..], None),
+        };
+        let terms = inputs.split(',').collect::<Vec<&str>>();
+        if terms.len() != ndims.len() {
+            return Err(format!(
+                "einsum: subscripts '{}' name {} operands, but {} were given",
+                subscripts,
+                terms.len(),
+                ndims.len()
+            ));
+        }
+
+        let parsed = terms
+            .iter()
+            .map(|term| parse_term(term))
+            .collect::<Result<Vec<_>, String>>()?;
+        let mut ellipsis_ndims = Vec::with_capacity(ndims.len());
+        for (idx, (labels, &ndim)) in parsed.iter().zip(ndims.iter()).enumerate() {
+            let named = labels.iter().filter(|label| label.is_some()).count();
+            let has_ellipsis = labels.len() > named;
+            if named > ndim || (!has_ellipsis && named != ndim) {
+                return Err(format!(
+                    "einsum: subscripts '{}' do not match the {} dimensions of operand {}",
+                    terms[idx], ndim, idx
+                ));
+            }
+            ellipsis_ndims.push(ndim - named);
+        }
+        let broadcast_ndim = ellipsis_ndims.iter().copied().max().unwrap_or(0);
+
+        let expand = |labels: &[Option<char>], ellipsis_ndim: usize| {
+            labels
+                .iter()
+                .flat_map(|label| match label {
+                    Some(c) => vec![Label::Named(*c)],
+                    None => (broadcast_ndim - ellipsis_ndim..broadcast_ndim)
+                        .map(Label::Broadcast)
+                        .collect(),
+                })
+                .collect::<Vec<Label>>()
+        };
+        let inputs = parsed
+            .iter()
+            .zip(ellipsis_ndims.iter())
+            .map(|(labels, &ellipsis_ndim)| expand(labels, ellipsis_ndim))
+            .collect::<Vec<Vec<Label>>>();
+
+        let output = match output {
+            Some(term) => {
+                let output = expand(&parse_term(term)?, broadcast_ndim);
+                for (idx, label) in output.iter().enumerate() {
+                    if output[..idx].contains(label) {
+                        return Err(format!(
+                            "einsum: label {} appears more than once in output '{}'",
+                            label, term
+                        ));
+                    }
+                    if !inputs.iter().any(|input| input.contains(label)) {
+                        return Err(format!(
+                            "einsum: output label {} does not appear in any input",
+                            label
+                        ));
+                    }
+                }
+                output
+            }
+            None => {
+                // NumPy's implicit mode: broadcast axes, followed by the
+                // labels that appear exactly once in alphabetical order.
+                let mut counts = BTreeMap::new();
+                for label in inputs.iter().flatten() {
+                    *counts.entry(*label).or_insert(0) += 1;
+                }
+                counts
+                    .into_iter()
+                    .filter(|&(label, count)| matches!(label, Label::Broadcast(_)) || count == 1)
+                    .map(|(label, _)| label)
+                    .collect()
+            }
+        };
+        Ok(Self { inputs, output })
+    }
+
+    /// Size of every label, broadcasting axes of length 1.
+    pub fn sizes(&self, shapes: &[&[usize]]) -> Result<BTreeMap<Label, usize>, String> {
+        let mut sizes = BTreeMap::new();
+        let mut owners = BTreeMap::new();
+        for (idx, (labels, shape)) in self.inputs.iter().zip(shapes.iter()).enumerate() {
+            for (axis, (&label, &dim)) in labels.iter().zip(shape.iter()).enumerate() {
+                if let Some(previous) = labels[..axis].iter().position(|&l| l == label) {
+                    if shape[previous] != dim {
+                        return Err(format!(
+                            "einsum: repeated label {} of operand {} has sizes {} and {} in shape {:?}",
+                            label, idx, shape[previous], dim, shape
+                        ));
+                    }
+                }
+                let size = sizes.entry(label).or_insert(dim);
+                let owner = *owners.entry(label).or_insert(idx);
+                if *size == 1 {
+                    *size = dim;
+                    owners.insert(label, idx);
+                } else if dim != 1 && dim != *size {
+                    return Err(format!(
+                        "einsum: label {} has size {} in operand {} with shape {:?}, but size {} in operand {} with shape {:?}",
+                        label, dim, idx, shape, size, owner, shapes[owner]
+                    ));
+                }
+            }
+        }
+        Ok(sizes)
+    }
+}
+
+/// View of an operand with axes `input` laid out along `labels`.
+///
+/// Repeated labels collapse onto their diagonal by adding up their strides,
+/// and labels that the operand lacks or holds with length 1 get a stride of
+/// zero, so the result never needs a copy.
+pub(crate) fn label_view(
+    view: &StridedView,
+    input: &[Label],
+    labels: &[Label],
+    sizes: &BTreeMap<Label, usize>,
+) -> StridedView {
+    let shape = labels
+        .iter()
+        .map(|label| sizes[label])
+        .collect::<Vec<usize>>();
+    let stride = labels
+        .iter()
+        .map(|label| {
+            input
+                .iter()
+                .zip(view.shape().iter().zip(view.stride().iter()))
+                .filter(|&(l, (&dim, _))| l == label && dim != 1)
+                .fold(0usize, |acc, (_, (_, &stride))| acc.wrapping_add(stride))
+        })
+        .collect::<Vec<usize>>();
+    StridedView::new_with_offset(shape, stride, view.offset())
+}
+
+/// Evaluates a single einsum step over in-memory operands, returning the
+/// result in row-major order of `output`.
+///
+/// Every operand is walked along the combined index space of the output
+/// labels followed by the summed labels, while a view of the output with zero
+/// strides on the summed labels maps each product to its accumulator.
+pub(crate) fn sum_product<T: Num + Clone>(
+    operands: &[(&[T], &StridedView, &[Label])],
+    output: &[Label],
+    sizes: &BTreeMap<Label, usize>,
+) -> Vec<T> {
+    let mut labels = output.to_vec();
+    for &(_, _, input) in operands {
+        for label in input {
+            if !labels.contains(label) {
+                labels.push(*label);
+            }
+        }
+    }
+
+    let out_view = ContiguousView::new(output.iter().map(|label| sizes[label]).collect::<Vec<_>>());
+    let mut out_stride = out_view.stride().to_vec();
+    out_stride.resize(labels.len(), 0);
+    let full_shape = labels
+        .iter()
+        .map(|label| sizes[label])
+        .collect::<Vec<usize>>();
+    let out_iter = StridedView::new(&full_shape, out_stride).translate_iter();
+
+    let mut iters = operands
+        .iter()
+        .map(|&(_, view, input)| label_view(view, input, &labels, sizes).translate_iter())
+        .collect::<Vec<_>>();
+    let mut data = Vec::new();
+    data.resize_with(out_view.numel(), T::zero);
+    for o in out_iter {
+        let product = operands
+            .iter()
+            .zip(iters.iter_mut())
+            .fold(T::one(), |acc, (&(storage, _, _), iter)| {
+                acc * storage[iter.next().unwrap()].clone()
+            });
+        data[o] = std::mem::replace(&mut data[o], T::zero()) + product;
+    }
+    data
+}
+
+/// Parses `subscripts` against `operands` and works out the size of every
+/// label.
+fn prepare<StorageType>(
+    subscripts: &str,
+    operands: &[Array<StorageType, StridedView>],
+) -> Result<(Subscripts, BTreeMap<Label, usize>), String>
+where
+    StorageType: Storage,
+    StorageType::Stored: Num,
+{
+    if operands.is_empty() {
+        return Err("einsum: at least one operand is required".to_string());
+    }
+    let ndims = operands.iter().map(|a| a.ndim()).collect::<Vec<usize>>();
+    let spec = Subscripts::parse(subscripts, &ndims)?;
+    let shapes = operands
+        .iter()
+        .map(|a| a.shape())
+        .collect::<Vec<&[usize]>>();
+    let sizes = spec.sizes(&shapes)?;
+    Ok((spec, sizes))
+}
+
+/// Evaluates the Einstein summation described by `subscripts` over
+/// `operands`, with the semantics of NumPy's `einsum`.
+///
+/// Subscripts name the axes of each operand with letters, separated by commas,
+/// optionally followed by `->` and the axes of the output. Without an explicit
+/// output, the result has the broadcast ellipsis axes followed by the labels
+/// that appear exactly once, in alphabetical order. Labels repeated within an
+/// operand take its diagonal, and labels missing from the output are summed.
+/// An ellipsis (`...`) stands for any remaining axes, which are broadcast
+/// across operands.
+///
+/// A single operand that needs no summation, such as a transpose or a
+/// diagonal, is returned as a view sharing storage with the operand. Three or
+/// more operands are contracted pairwise in the order picked by
+/// [`Optimize::Greedy`]; see [`einsum_with`] to choose another strategy.
+///
+/// Operands may mix view types, so contiguous arrays can be passed together
+/// with slices and transposes.
+pub fn einsum<T, StorageType>(
+    subscripts: &str,
+    operands: &[&dyn AsStrided<StorageType>],
+) -> Result<Array<StorageType, StridedView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    einsum_with(subscripts, operands, Optimize::Greedy)
+}
+
+/// Like [`einsum`], but contracts three or more operands in the order chosen
+/// by `optimize`.
+pub fn einsum_with<T, StorageType>(
+    subscripts: &str,
+    operands: &[&dyn AsStrided<StorageType>],
+    optimize: Optimize,
+) -> Result<Array<StorageType, StridedView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    let operands = operands.iter().map(|a| a.as_strided()).collect::<Vec<_>>();
+    let (spec, sizes) = prepare(subscripts, &operands)?;
+    let views = operands
+        .iter()
+        .map(|a| a.view().clone())
+        .collect::<Vec<StridedView>>();
+
+    if operands.len() == 1
+        && spec.inputs[0]
+            .iter()
+            .all(|label| spec.output.contains(label))
+    {
+        let view = label_view(&views[0], &spec.inputs[0], &spec.output, &sizes);
+        return Ok(Array::from_parts(operands[0].storage().clone(), view));
+    }
+
+    // Take a single guard per distinct storage, a pending writer could
+    // otherwise wedge itself between two read locks on the same storage.
+    let mut guards = Vec::new();
+    let mut guard_idx = Vec::with_capacity(operands.len());
+    for (idx, operand) in operands.iter().enumerate() {
+        match operands[..idx]
+            .iter()
+            .position(|other| other.storage().storage_ptr_eq(operand.storage()))
+        {
+            Some(previous) => guard_idx.push(guard_idx[previous]),
+            None => {
+                guard_idx.push(guards.len());
+                guards.push(
+                    operand
+                        .storage()
+                        .storage_get()
+                        .map_err(|err| err.to_string())?,
+                );
+            }
+        }
+    }
+
+    let shape_of = |labels: &[Label]| labels.iter().map(|label| sizes[label]).collect::<Vec<_>>();
+    let path = path::contraction_path(&spec.inputs, &spec.output, &sizes, optimize);
+    let data = if path.steps.is_empty() {
+        let inputs = guard_idx
+            .iter()
+            .zip(views.iter().zip(spec.inputs.iter()))
+            .map(|(&g, (view, input))| (&guards[g][..], view, &input[..]))
+            .collect::<Vec<_>>();
+        sum_product(&inputs, &spec.output, &sizes)
+    } else {
+        // Intermediates are written out contiguously, while the operands
+        // themselves are only ever read through their views.
+        let mut remaining = guard_idx
+            .iter()
+            .zip(views.into_iter().zip(spec.inputs))
+            .map(|(&g, (view, input))| (Cow::Borrowed(&guards[g][..]), view, input))
+            .collect::<Vec<(Cow<[T]>, StridedView, Vec<Label>)>>();
+        for step in &path.steps {
+            let (i, j) = step.operands;
+            let rhs = remaining.remove(j);
+            let lhs = remaining.remove(i);
+            let data = sum_product(
+                &[
+                    (lhs.0.as_ref(), &lhs.1, &lhs.2[..]),
+                    (rhs.0.as_ref(), &rhs.1, &rhs.2[..]),
+                ],
+                &step.output,
+                &sizes,
+            );
+            let view = StridedView::from_view(&ContiguousView::new(shape_of(&step.output)));
+            remaining.push((Cow::Owned(data), view, step.output.clone()));
+        }
+        remaining.pop().unwrap().0.into_owned()
+    };
+
+    Ok(Array::from_parts(
+        StorageType::from(data),
+        StridedView::from_view(&ContiguousView::new(shape_of(&spec.output))),
+    ))
+}
+
+/// Reports the order in which [`einsum_with`] contracts `operands` under
+/// `optimize`, along with its estimated cost. Only the shapes of the operands
+/// are inspected.
+pub fn einsum_path<StorageType>(
+    subscripts: &str,
+    operands: &[&dyn AsStrided<StorageType>],
+    optimize: Optimize,
+) -> Result<ContractionPath, String>
+where
+    StorageType: Storage,
+    StorageType::Stored: Num,
+{
+    let operands = operands.iter().map(|a| a.as_strided()).collect::<Vec<_>>();
+    let (spec, sizes) = prepare(subscripts, &operands)?;
+    Ok(path::contraction_path(
+        &spec.inputs,
+        &spec.output,
+        &sizes,
+        optimize,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::arange;
+
+    #[test]
+    fn parse_implicit_output() {
+        let spec = Subscripts::parse("ij,jk", &[2, 2]).unwrap();
+        let (i, j, k) = (Label::Named('i'), Label::Named('j'), Label::Named('k'));
+        assert_eq!(spec.inputs, vec![vec![i, j], vec![j, k]]);
+        assert_eq!(spec.output, vec![i, k]);
+
+        let spec = Subscripts::parse("...ba, a", &[3, 1]).unwrap();
+        assert_eq!(spec.output, vec![Label::Broadcast(0), Label::Named('b')]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Subscripts::parse("ij,jk", &[2]).is_err());
+        assert!(Subscripts::parse("ij", &[3]).is_err());
+        assert!(Subscripts::parse("i.j", &[2]).is_err());
+        assert!(Subscripts::parse("......", &[2]).is_err());
+        assert!(Subscripts::parse("ij->ii", &[2]).is_err());
+        assert!(Subscripts::parse("ij->k", &[2]).is_err());
+    }
+
+    #[test]
+    fn matrix_product() {
+        let a = arange(&[2, 3]);
+        let b = arange(&[3, 2]);
+        let c = einsum("ij,jk->ik", &[&a, &b]).unwrap();
+        assert_eq!(c.shape(), &[2, 2]);
+        assert_eq!(c.to_vec(), a.matmul(&b).unwrap().to_vec());
+        assert_eq!(einsum("ij,jk", &[&a, &b]).unwrap().to_vec(), c.to_vec());
+        let ct = einsum("ij,jk->ki", &[&a, &b]).unwrap();
+        assert_eq!(ct.to_vec(), vec![10, 28, 13, 40]);
+    }
+
+    #[test]
+    fn transpose_and_diagonal_are_views() {
+        let a = arange(&[3, 3]);
+        let t = einsum("ij->ji", &[&a]).unwrap();
+        assert!(t.storage().storage_ptr_eq(a.storage()));
+        assert_eq!(t.to_vec(), a.t().to_vec());
+        assert_eq!(einsum("ba", &[&a]).unwrap().to_vec(), a.t().to_vec());
+
+        let d = einsum("ii->i", &[&a]).unwrap();
+        assert!(d.storage().storage_ptr_eq(a.storage()));
+        assert_eq!(d.to_vec(), vec![0, 4, 8]);
+    }
+
+    #[test]
+    fn trace_and_sums() {
+        let a = arange(&[3, 3]);
+        assert_eq!(einsum("ii", &[&a]).unwrap().item(), 12);
+        assert_eq!(einsum("ij->", &[&a]).unwrap().item(), 36);
+        assert_eq!(einsum("ij->j", &[&a]).unwrap().to_vec(), vec![9, 12, 15]);
+
+        let b = arange(&[2, 2, 2]);
+        assert_eq!(einsum("iii->i", &[&b]).unwrap().to_vec(), vec![0, 7]);
+        assert_eq!(einsum("iji->j", &[&b]).unwrap().to_vec(), vec![5, 9]);
+    }
+
+    #[test]
+    fn outer_and_inner_products() {
+        let v = arange(&[3]);
+        let w = arange(&[2]);
+        let outer = einsum("i,j", &[&v, &w]).unwrap();
+        assert_eq!(outer.shape(), &[3, 2]);
+        assert_eq!(outer.to_vec(), vec![0, 0, 0, 1, 0, 2]);
+        assert_eq!(einsum("i,i", &[&v, &v]).unwrap().item(), 5);
+        assert_eq!(einsum("i,i,i->", &[&v, &v, &v]).unwrap().item(), 9);
+    }
+
+    #[test]
+    fn ellipsis_broadcasting() {
+        let a = arange(&[2, 2, 3]);
+        let b = arange(&[3, 2]);
+        let c = einsum("...ij,...jk->...ik", &[&a, &b]).unwrap();
+        assert_eq!(c.shape(), &[2, 2, 2]);
+        assert_eq!(c.to_vec(), a.matmul(&b).unwrap().to_vec());
+
+        let row = arange(&[1, 3]);
+        let m = arange(&[2, 3]);
+        let c = einsum("...j,...j->...", &[&row, &m]).unwrap();
+        assert_eq!(c.to_vec(), vec![5, 14]);
+        assert_eq!(einsum("i...->...", &[&m]).unwrap().to_vec(), vec![3, 5, 7]);
+    }
+
+    #[test]
+    fn strided_operands() {
+        let a = arange(&[3, 4]);
+        let s = a.slice(crate::s![..;2, 1..]);
+        let c = einsum("ij,kj->ik", &[&s, &s]).unwrap();
+        assert_eq!(c.to_vec(), vec![14, 62, 62, 302]);
+
+        let b = arange(&[3, 4]);
+        let c = einsum("ij,jk->ik", &[&a, &b.t()]).unwrap();
+        assert_eq!(c.to_vec(), a.matmul(&b.t()).unwrap().to_vec());
+
+        let operands: [&dyn AsStrided<_>; 3] = [&a, &b.t(), &s.t()];
+        let path = einsum_path("ij,jk,kl", &operands, Optimize::Greedy).unwrap();
+        let steps = path
+            .steps
+            .iter()
+            .map(|step| (step.operands, step.subscripts.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(steps, vec![((0, 1), "ij,jk->ik"), ((0, 1), "kl,ik->il")]);
+        let c = einsum("ij,jk,kl", &operands).unwrap();
+        let expected = a.matmul(&b.t()).unwrap().matmul(&s.t()).unwrap();
+        assert_eq!(c.to_vec(), expected.to_vec());
+    }
+
+    #[test]
+    fn size_mismatch_names_shapes() {
+        let a = arange(&[2, 3]);
+        let b = arange(&[2, 3]);
+        let err = einsum("ij,jk", &[&a, &b]).err().unwrap();
+        assert!(err.contains("'j'"), "{}", err);
+        assert!(err.contains("[2, 3]"), "{}", err);
+        assert!(einsum("ii", &[&a]).is_err());
+    }
+
+    #[test]
+    fn contraction_strategies_agree() {
+        let a = arange(&[3, 4]);
+        let b = arange(&[4, 5, 2]);
+        let c = arange(&[2, 3]);
+        let d = arange(&[5]);
+        let subscripts = "ij,jkl,li,k->i";
+        let expected = einsum_with(subscripts, &[&a, &b, &c, &d], Optimize::None).unwrap();
+        for optimize in [Optimize::Greedy, Optimize::Optimal] {
+            let result = einsum_with(subscripts, &[&a, &b, &c, &d], optimize).unwrap();
+            assert_eq!(result.shape(), &[3]);
+            assert_eq!(result.to_vec(), expected.to_vec());
+        }
+    }
+
+    #[test]
+    fn matrix_chain_follows_path() {
+        let a = arange(&[6, 2]);
+        let b = arange(&[2, 6]);
+        let c = arange(&[6, 2]);
+        let path = einsum_path("ij,jk,kl->il", &[&a, &b, &c], Optimize::Greedy).unwrap();
+        assert_eq!(path.steps[0].operands, (1, 2));
+        let expected = a.matmul(&b.matmul(&c).unwrap()).unwrap();
+        let result = einsum("ij,jk,kl->il", &[&a, &b, &c]).unwrap();
+        assert_eq!(result.to_vec(), expected.to_vec());
+        assert!(path.to_string().contains("jk,kl->jl"), "{}", path);
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::Label;
+
+/// How [`einsum`](super::einsum) orders the pairwise contractions of three or
+/// more operands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Optimize {
+    /// Contract every operand at once, as a single nested loop.
+    None,
+    /// Repeatedly contract the pair of operands that shrinks the total
+    /// number of elements the most.
+    Greedy,
+    /// Search every pairwise order for the fewest floating point operations.
+    /// The search is exponential in the number of operands.
+    Optimal,
+}
+
+/// A single pairwise contraction in a [`ContractionPath`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractionStep {
+    /// Positions of the contracted operands in the list of remaining
+    /// operands. Both are removed and the result is appended to the end.
+    pub operands: (usize, usize),
+    /// The contraction in einsum notation.
+    pub subscripts: String,
+    /// Estimated floating point operations of the contraction.
+    pub flops: usize,
+    /// Number of elements in the result.
+    pub size: usize,
+    pub(crate) output: Vec<Label>,
+}
+
+/// The order in which an einsum expression is evaluated, as reported by
+/// [`einsum_path`](super::einsum_path).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractionPath {
+    pub steps: Vec<ContractionStep>,
+    /// Estimated floating point operations of evaluating the expression in a
+    /// single nested loop.
+    pub naive_flops: usize,
+    /// Estimated floating point operations of evaluating the steps in order.
+    pub optimized_flops: usize,
+    /// Number of elements in the largest intermediate result.
+    pub largest_intermediate: usize,
+}
+
+impl fmt::Display for ContractionPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Naive FLOP count: {}", self.naive_flops)?;
+        writeln!(f, "  Optimized FLOP count: {}", self.optimized_flops)?;
+        writeln!(
+            f,
+            "  Largest intermediate: {} elements",
+            self.largest_intermediate
+        )?;
+        write!(f, "  {:<10} {:>12}  subscripts", "operands", "flops")?;
+        for step in &self.steps {
+            let operands = format!("({}, {})", step.operands.0, step.operands.1);
+            write!(
+                f,
+                "\n  {:<10} {:>12}  {}",
+                operands, step.flops, step.subscripts
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders labels in einsum notation, collapsing broadcast axes to `...`.
+pub(crate) fn render(labels: &[Label]) -> String {
+    let mut term = String::new();
+    for (idx, label) in labels.iter().enumerate() {
+        match label {
+            Label::Named(c) => term.push(*c),
+            Label::Broadcast(_) => {
+                if idx == 0 || !matches!(labels[idx - 1], Label::Broadcast(_)) {
+                    term.push_str("...");
+                }
+            }
+        }
+    }
+    term
+}
+
+fn size_of<'a, I: IntoIterator<Item = &'a Label>>(
+    labels: I,
+    sizes: &BTreeMap<Label, usize>,
+) -> usize {
+    labels
+        .into_iter()
+        .fold(1usize, |acc, label| acc.saturating_mul(sizes[label]))
+}
+
+/// Floating point operations of contracting `terms` operands over the
+/// `labels` index space, counting one operation per multiplication and one
+/// more if the contraction sums over any label.
+fn flop_count(
+    labels: &BTreeSet<Label>,
+    inner: bool,
+    terms: usize,
+    sizes: &BTreeMap<Label, usize>,
+) -> usize {
+    let factor = terms.saturating_sub(1).max(1) + inner as usize;
+    size_of(labels, sizes).saturating_mul(factor)
+}
+
+/// Plans contracting operands `i` and `j` of `remaining`, keeping only the
+/// labels still needed by the other operands or by `output`.
+fn plan_step(
+    remaining: &[Vec<Label>],
+    (i, j): (usize, usize),
+    output: &[Label],
+    sizes: &BTreeMap<Label, usize>,
+) -> ContractionStep {
+    let union = remaining[i]
+        .iter()
+        .chain(remaining[j].iter())
+        .copied()
+        .collect::<BTreeSet<Label>>();
+    let step_output = if remaining.len() == 2 {
+        output.to_vec()
+    } else {
+        union
+            .iter()
+            .copied()
+            .filter(|label| {
+                output.contains(label)
+                    || remaining
+                        .iter()
+                        .enumerate()
+                        .any(|(k, labels)| k != i && k != j && labels.contains(label))
+            })
+            .collect()
+    };
+    let inner = union.len() > step_output.len();
+    ContractionStep {
+        operands: (i, j),
+        subscripts: format!(
+            "{},{}->{}",
+            render(&remaining[i]),
+            render(&remaining[j]),
+            render(&step_output)
+        ),
+        flops: flop_count(&union, inner, 2, sizes),
+        size: size_of(&step_output, sizes),
+        output: step_output,
+    }
+}
+
+/// Removes the operands contracted by `step` and appends its result.
+fn apply_step(remaining: &mut Vec<Vec<Label>>, step: &ContractionStep) {
+    let (i, j) = step.operands;
+    remaining.remove(j);
+    remaining.remove(i);
+    remaining.push(step.output.clone());
+}
+
+fn greedy(
+    inputs: &[Vec<Label>],
+    output: &[Label],
+    sizes: &BTreeMap<Label, usize>,
+) -> Vec<ContractionStep> {
+    let mut remaining = inputs.to_vec();
+    let mut steps = Vec::new();
+    while remaining.len() > 1 {
+        let mut best: Option<(isize, usize, ContractionStep)> = None;
+        for i in 0..remaining.len() {
+            for j in i + 1..remaining.len() {
+                let step = plan_step(&remaining, (i, j), output, sizes);
+                let removed = size_of(&remaining[i], sizes) + size_of(&remaining[j], sizes);
+                let cost = step.size as isize - removed as isize;
+                if best.as_ref().is_none_or(|(best_cost, best_flops, _)| {
+                    (cost, step.flops) < (*best_cost, *best_flops)
+                }) {
+                    best = Some((cost, step.flops, step));
+                }
+            }
+        }
+        let (_, _, step) = best.unwrap();
+        apply_step(&mut remaining, &step);
+        steps.push(step);
+    }
+    steps
+}
+
+/// Depth-first search over every pairwise order, pruning any partial order
+/// that already costs more than the best complete one.
+fn optimal(
+    remaining: &[Vec<Label>],
+    output: &[Label],
+    sizes: &BTreeMap<Label, usize>,
+    steps: &mut Vec<ContractionStep>,
+    flops: usize,
+    best: &mut Option<(usize, Vec<ContractionStep>)>,
+) {
+    if remaining.len() == 1 {
+        if best
+            .as_ref()
+            .is_none_or(|(best_flops, _)| flops < *best_flops)
+        {
+            *best = Some((flops, steps.clone()));
+        }
+        return;
+    }
+    for i in 0..remaining.len() {
+        for j in i + 1..remaining.len() {
+            let step = plan_step(remaining, (i, j), output, sizes);
+            let total = flops.saturating_add(step.flops);
+            if best
+                .as_ref()
+                .is_some_and(|(best_flops, _)| total >= *best_flops)
+            {
+                continue;
+            }
+            let mut next = remaining.to_vec();
+            apply_step(&mut next, &step);
+            steps.push(step);
+            optimal(&next, output, sizes, steps, total, best);
+            steps.pop();
+        }
+    }
+}
+
+/// Plans the evaluation of `inputs` into `output` with the given strategy.
+pub(crate) fn contraction_path(
+    inputs: &[Vec<Label>],
+    output: &[Label],
+    sizes: &BTreeMap<Label, usize>,
+    optimize: Optimize,
+) -> ContractionPath {
+    let all_labels = inputs
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<BTreeSet<Label>>();
+    let naive_flops = flop_count(
+        &all_labels,
+        all_labels.len() > output.len(),
+        inputs.len(),
+        sizes,
+    );
+
+    let steps = if inputs.len() < 3 {
+        Vec::new()
+    } else {
+        match optimize {
+            Optimize::None => Vec::new(),
+            Optimize::Greedy => greedy(inputs, output, sizes),
+            Optimize::Optimal => {
+                let mut best = None;
+                optimal(inputs, output, sizes, &mut Vec::new(), 0, &mut best);
+                best.map(|(_, steps)| steps).unwrap_or_default()
+            }
+        }
+    };
+
+    let (optimized_flops, largest_intermediate) = if steps.is_empty() {
+        (naive_flops, size_of(output, sizes))
+    } else {
+        (
+            steps
+                .iter()
+                .fold(0usize, |acc, step| acc.saturating_add(step.flops)),
+            steps.iter().map(|step| step.size).max().unwrap_or(1),
+        )
+    };
+    ContractionPath {
+        steps,
+        naive_flops,
+        optimized_flops,
+        largest_intermediate,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::einsum::Subscripts;
+
+    fn plan(subscripts: &str, shapes: &[&[usize]], optimize: Optimize) -> ContractionPath {
+        let ndims = shapes.iter().map(|shape| shape.len()).collect::<Vec<_>>();
+        let spec = Subscripts::parse(subscripts, &ndims).unwrap();
+        let sizes = spec.sizes(shapes).unwrap();
+        contraction_path(&spec.inputs, &spec.output, &sizes, optimize)
+    }
+
+    #[test]
+    fn greedy_contracts_the_small_pair_first() {
+        let path = plan(
+            "ij,jk,kl->il",
+            &[&[100, 2], &[2, 100], &[100, 2]],
+            Optimize::Greedy,
+        );
+        let operands = path
+            .steps
+            .iter()
+            .map(|step| step.operands)
+            .collect::<Vec<_>>();
+        assert_eq!(operands, vec![(1, 2), (0, 1)]);
+        assert_eq!(path.steps[0].subscripts, "jk,kl->jl");
+        assert_eq!(path.steps[1].subscripts, "ij,jl->il");
+        assert_eq!(path.optimized_flops, 1600);
+        assert_eq!(path.largest_intermediate, 200);
+        assert!(path.naive_flops > path.optimized_flops);
+    }
+
+    #[test]
+    fn optimal_never_loses_to_greedy() {
+        let shapes: [&[usize]; 4] = [&[8, 4], &[4, 16, 2], &[2, 8], &[16, 8, 8]];
+        let subscripts = "ab,bcd,de,cea->";
+        let greedy = plan(subscripts, &shapes, Optimize::Greedy);
+        let optimal = plan(subscripts, &shapes, Optimize::Optimal);
+        assert_eq!(optimal.steps.len(), 3);
+        assert!(optimal.optimized_flops <= greedy.optimized_flops);
+    }
+
+    #[test]
+    fn no_steps_for_few_operands() {
+        let path = plan("ij,jk", &[&[2, 3], &[3, 4]], Optimize::Optimal);
+        assert!(path.steps.is_empty());
+        assert_eq!(path.naive_flops, 2 * 3 * 4 * 2);
+        assert_eq!(path.optimized_flops, path.naive_flops);
+        assert_eq!(path.largest_intermediate, 8);
+
+        let path = plan("i,i,i", &[&[3], &[3], &[3]], Optimize::None);
+        assert!(path.steps.is_empty());
+    }
+
+    #[test]
+    fn render_collapses_broadcast_axes() {
+        let labels = [Label::Broadcast(0), Label::Broadcast(1), Label::Named('i')];
+        assert_eq!(render(&labels), "...i");
+        let path = plan(
+            "...ij,...jk,...kl",
+            &[&[5, 2, 3], &[3, 4], &[4, 2]],
+            Optimize::Greedy,
+        );
+        assert!(path.to_string().contains("->...il"), "{}", path);
+    }
+}
@@ -32,9 +32,8 @@ impl<'a, T: Num + fmt::Display, ViewType: ArrayView> ArrayFormatter
             2 => {
                 let mut s = String::new();
                 let nrows = self.view.shape()[0];
+                let ncols = self.view.shape()[1];
                 for row in 0usize..nrows {
-                    let start = self.view.translate([row, 0]);
-                    let end = self.view.translate([row + 1, 0]);
                     s.push(match row {
                         0 => '[',
                         _ => ' ',
@@ -42,9 +41,8 @@ impl<'a, T: Num + fmt::Display, ViewType: ArrayView> ArrayFormatter
                     s.push_str(
                         format!(
                             "[{}]",
-                            &self.storage[start..end]
-                                .iter()
-                                .map(|x| x.to_string())
+                            (0usize..ncols)
+                                .map(|col| self.storage[self.view.translate([row, col])].to_string())
                                 .collect::<Vec<String>>()
                                 .join(",")
                         )
@@ -58,9 +56,9 @@ impl<'a, T: Num + fmt::Display, ViewType: ArrayView> ArrayFormatter
                 write!(
                     f,
                     "[{}]",
-                    self.storage
-                        .iter()
-                        .map(|x| x.to_string())
+                    self.view
+                        .translate_iter()
+                        .map(|i| self.storage[i].to_string())
                         .collect::<Vec<String>>()
                         .join(",")
                 )
@@ -0,0 +1,416 @@
+use std::any::TypeId;
+use std::convert::TryInto;
+
+use num_traits::Num;
+
+/// Location of a strided matrix inside a storage slice. Strides follow the
+/// wrapping convention of [`StridedView`](crate::views::StridedView).
+#[derive(Clone, Copy, Debug)]
+pub struct MatrixLayout {
+    pub offset: usize,
+    pub row_stride: usize,
+    pub col_stride: usize,
+}
+
+impl MatrixLayout {
+    pub fn index(&self, row: usize, col: usize) -> usize {
+        self.offset
+            .wrapping_add(row.wrapping_mul(self.row_stride))
+            .wrapping_add(col.wrapping_mul(self.col_stride))
+    }
+}
+
+/// Computes the `m x n` product of an `m x k` and a `k x n` matrix, writing
+/// it into `out` in row-major order.
+///
+/// Any numeric type goes through a plain triple loop; large `f32` and `f64`
+/// products are dispatched to a cache-blocked kernel instead.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn gemm<T: Num + Clone + 'static>(
+    m: usize,
+    n: usize,
+    k: usize,
+    a: &[T],
+    a_layout: MatrixLayout,
+    b: &[T],
+    b_layout: MatrixLayout,
+    out: &mut [T],
+) {
+    if m * n * k >= BLOCKED_THRESHOLD {
+        if let (Some(a), Some(b), Some(out)) = (cast::<T, f64>(a), cast(b), cast_mut(out)) {
+            return gemm_blocked(m, n, k, a, a_layout, b, b_layout, out);
+        }
+        if let (Some(a), Some(b), Some(out)) = (cast::<T, f32>(a), cast(b), cast_mut(out)) {
+            return gemm_blocked(m, n, k, a, a_layout, b, b_layout, out);
+        }
+    }
+    gemm_naive(m, n, k, a, a_layout, b, b_layout, out)
+}
+
+/// Reinterprets `slice` as a slice of `U` if `T` is `U`.
+fn cast<T: 'static, U: 'static>(slice: &[T]) -> Option<&[U]> {
+    if TypeId::of::<T>() == TypeId::of::<U>() {
+        // SAFETY: equal `TypeId`s mean `T` and `U` are one type, so the
+        // pointer cast keeps size, alignment and validity of every element.
+        // The new slice has the same length and borrows `slice` for its
+        // whole lifetime.
+        Some(unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const U, slice.len()) })
+    } else {
+        None
+    }
+}
+
+/// Reinterprets `slice` as a mutable slice of `U` if `T` is `U`.
+fn cast_mut<T: 'static, U: 'static>(slice: &mut [T]) -> Option<&mut [U]> {
+    if TypeId::of::<T>() == TypeId::of::<U>() {
+        // SAFETY: as in `cast`, `T` and `U` are one type. The new slice
+        // reborrows `slice` mutably, so it is the only live reference to
+        // these elements until it is dropped.
+        Some(unsafe { std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut U, slice.len()) })
+    } else {
+        None
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn gemm_naive<T: Num + Clone>(
+    m: usize,
+    n: usize,
+    k: usize,
+    a: &[T],
+    a_layout: MatrixLayout,
+    b: &[T],
+    b_layout: MatrixLayout,
+    out: &mut [T],
+) {
+    for i in 0..m {
+        for j in 0..n {
+            let mut acc = T::zero();
+            for p in 0..k {
+                acc = acc + a[a_layout.index(i, p)].clone() * b[b_layout.index(p, j)].clone();
+            }
+            out[i * n + j] = acc;
+        }
+    }
+}
+
+/// Below this many multiply-adds packing costs more than it saves.
+const BLOCKED_THRESHOLD: usize = 32 * 32 * 32;
+
+/// Rows of the register tile computed by the microkernel.
+const MR: usize = 4;
+/// Columns of the register tile computed by the microkernel.
+const NR: usize = 8;
+/// Depth of a packed panel, sized so a `KC x NR` panel of `B` stays in L1.
+const KC: usize = 256;
+/// Rows of a packed block of `A`, sized so a `MC x KC` block stays in L2.
+const MC: usize = 64;
+/// Columns of a packed block of `B`.
+const NC: usize = 2048;
+
+/// Cache-blocked matrix product in the style of BLIS.
+///
+/// `B` is packed into `KC x NR` column panels and `A` into `MR x KC` row
+/// panels, both zero-padded to full tiles, so the microkernel only ever sees
+/// unit-stride data of a fixed size. Packing reads through the layouts, which
+/// lets transposed and otherwise strided operands go through the same path.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn gemm_blocked<T: Num + Copy>(
+    m: usize,
+    n: usize,
+    k: usize,
+    a: &[T],
+    a_layout: MatrixLayout,
+    b: &[T],
+    b_layout: MatrixLayout,
+    out: &mut [T],
+) {
+    out[..m * n].iter_mut().for_each(|x| *x = T::zero());
+
+    let mut packed_a = vec![T::zero(); MC.div_ceil(MR) * MR * KC];
+    let mut packed_b = vec![T::zero(); NC.div_ceil(NR) * NR * KC];
+
+    for jc in (0..n).step_by(NC) {
+        let nc = NC.min(n - jc);
+        for pc in (0..k).step_by(KC) {
+            let kc = KC.min(k - pc);
+            pack_b(kc, nc, b, b_layout, pc, jc, &mut packed_b);
+
+            for ic in (0..m).step_by(MC) {
+                let mc = MC.min(m - ic);
+                pack_a(mc, kc, a, a_layout, ic, pc, &mut packed_a);
+
+                for jr in (0..nc).step_by(NR) {
+                    let b_panel = &packed_b[jr * kc..(jr + NR) * kc];
+                    for ir in (0..mc).step_by(MR) {
+                        let a_panel = &packed_a[ir * kc..(ir + MR) * kc];
+                        let mut acc = [[T::zero(); NR]; MR];
+                        microkernel(kc, a_panel, b_panel, &mut acc);
+
+                        let rows = MR.min(mc - ir);
+                        let cols = NR.min(nc - jr);
+                        for (i, acc_row) in acc.iter().enumerate().take(rows) {
+                            let start = (ic + ir + i) * n + jc + jr;
+                            for (c, &x) in out[start..start + cols].iter_mut().zip(acc_row) {
+                                *c = *c + x;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Packs the `mc x kc` block of `A` starting at (`row`, `col`) into row
+/// panels of height `MR`, laid out so that each step of `p` is contiguous.
+fn pack_a<T: Num + Copy>(
+    mc: usize,
+    kc: usize,
+    a: &[T],
+    layout: MatrixLayout,
+    row: usize,
+    col: usize,
+    packed: &mut [T],
+) {
+    for (panel, ir) in (0..mc).step_by(MR).enumerate() {
+        let dst = &mut packed[panel * MR * kc..(panel + 1) * MR * kc];
+        for p in 0..kc {
+            for i in 0..MR {
+                dst[p * MR + i] = if ir + i < mc {
+                    a[layout.index(row + ir + i, col + p)]
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+/// Packs the `kc x nc` block of `B` starting at (`row`, `col`) into column
+/// panels of width `NR`, laid out so that each step of `p` is contiguous.
+fn pack_b<T: Num + Copy>(
+    kc: usize,
+    nc: usize,
+    b: &[T],
+    layout: MatrixLayout,
+    row: usize,
+    col: usize,
+    packed: &mut [T],
+) {
+    for (panel, jr) in (0..nc).step_by(NR).enumerate() {
+        let dst = &mut packed[panel * NR * kc..(panel + 1) * NR * kc];
+        for p in 0..kc {
+            for j in 0..NR {
+                dst[p * NR + j] = if jr + j < nc {
+                    b[layout.index(row + p, col + jr + j)]
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+/// Accumulates the product of an `MR x kc` and a `kc x NR` packed panel into
+/// a register tile. The fixed-size inner loops are what the compiler turns
+/// into vector instructions.
+#[inline(always)]
+fn microkernel<T: Num + Copy>(kc: usize, a: &[T], b: &[T], acc: &mut [[T; NR]; MR]) {
+    for (a, b) in a.chunks_exact(MR).zip(b.chunks_exact(NR)).take(kc) {
+        let a: &[T; MR] = a.try_into().unwrap();
+        let b: &[T; NR] = b.try_into().unwrap();
+        for i in 0..MR {
+            for j in 0..NR {
+                acc[i][j] = acc[i][j] + a[i] * b[j];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate test;
+
+    use super::*;
+
+    /// Small xorshift generator, enough to get reproducible inputs.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next_f64(&mut self) -> f64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+        }
+
+        fn vec(&mut self, len: usize) -> Vec<f64> {
+            (0..len).map(|_| self.next_f64()).collect()
+        }
+    }
+
+    fn row_major(offset: usize, cols: usize) -> MatrixLayout {
+        MatrixLayout {
+            offset,
+            row_stride: cols,
+            col_stride: 1,
+        }
+    }
+
+    fn col_major(offset: usize, rows: usize) -> MatrixLayout {
+        MatrixLayout {
+            offset,
+            row_stride: 1,
+            col_stride: rows,
+        }
+    }
+
+    fn assert_close(lhs: &[f64], rhs: &[f64], tol: f64) {
+        assert_eq!(lhs.len(), rhs.len());
+        for (x, y) in lhs.iter().zip(rhs.iter()) {
+            assert!((x - y).abs() <= tol * (1.0 + y.abs()), "{} != {}", x, y);
+        }
+    }
+
+    #[test]
+    fn blocked_matches_naive_random() {
+        let mut rng = XorShift(0x2545f4914f6cdd1d);
+        for &(m, n, k) in &[
+            (1, 1, 1),
+            (5, 7, 3),
+            (67, 45, 133),
+            (130, 9, 300),
+            (3, 2100, 17),
+        ] {
+            let a = rng.vec(m * k + 3);
+            let b = rng.vec(k * n + 5);
+            let mut expected = vec![0.0; m * n];
+            let mut actual = vec![1.0; m * n];
+            gemm_naive(
+                m,
+                n,
+                k,
+                &a,
+                row_major(3, k),
+                &b,
+                row_major(5, n),
+                &mut expected,
+            );
+            gemm_blocked(
+                m,
+                n,
+                k,
+                &a,
+                row_major(3, k),
+                &b,
+                row_major(5, n),
+                &mut actual,
+            );
+            assert_close(&actual, &expected, 1e-12);
+        }
+    }
+
+    #[test]
+    fn blocked_matches_naive_transposed() {
+        let mut rng = XorShift(0x9e3779b97f4a7c15);
+        let (m, n, k) = (33, 70, 260);
+        let a = rng.vec(m * k);
+        let b = rng.vec(k * n);
+        let mut expected = vec![0.0; m * n];
+        let mut actual = vec![0.0; m * n];
+        gemm_naive(
+            m,
+            n,
+            k,
+            &a,
+            col_major(0, m),
+            &b,
+            col_major(0, k),
+            &mut expected,
+        );
+        gemm_blocked(
+            m,
+            n,
+            k,
+            &a,
+            col_major(0, m),
+            &b,
+            col_major(0, k),
+            &mut actual,
+        );
+        assert_close(&actual, &expected, 1e-12);
+    }
+
+    #[test]
+    fn blocked_f32_matches_naive() {
+        let mut rng = XorShift(42);
+        let (m, n, k) = (40, 50, 60);
+        let a = rng
+            .vec(m * k)
+            .iter()
+            .map(|&x| x as f32)
+            .collect::<Vec<f32>>();
+        let b = rng
+            .vec(k * n)
+            .iter()
+            .map(|&x| x as f32)
+            .collect::<Vec<f32>>();
+        let mut expected = vec![0.0f32; m * n];
+        let mut actual = vec![0.0f32; m * n];
+        gemm_naive(
+            m,
+            n,
+            k,
+            &a,
+            row_major(0, k),
+            &b,
+            row_major(0, n),
+            &mut expected,
+        );
+        gemm(
+            m,
+            n,
+            k,
+            &a,
+            row_major(0, k),
+            &b,
+            row_major(0, n),
+            &mut actual,
+        );
+        for (x, y) in actual.iter().zip(expected.iter()) {
+            assert!((x - y).abs() <= 1e-4 * (1.0 + y.abs()));
+        }
+    }
+
+    fn bench_inputs(size: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        let mut rng = XorShift(7);
+        (
+            rng.vec(size * size),
+            rng.vec(size * size),
+            vec![0.0; size * size],
+        )
+    }
+
+    #[bench]
+    fn bench_gemm_blocked_f64_128(bencher: &mut test::Bencher) {
+        let size = 128;
+        let (a, b, mut out) = bench_inputs(size);
+        bencher.iter(|| {
+            let (a_layout, b_layout) = (row_major(0, size), row_major(0, size));
+            gemm_blocked(size, size, size, &a, a_layout, &b, b_layout, &mut out);
+            test::black_box(&out);
+        });
+    }
+
+    #[bench]
+    fn bench_gemm_naive_f64_128(bencher: &mut test::Bencher) {
+        let size = 128;
+        let (a, b, mut out) = bench_inputs(size);
+        bencher.iter(|| {
+            let (a_layout, b_layout) = (row_major(0, size), row_major(0, size));
+            gemm_naive(size, size, size, &a, a_layout, &b, b_layout, &mut out);
+            test::black_box(&out);
+        });
+    }
+}
@@ -1,12 +1,30 @@
 #![feature(cell_filter_map)]
 #![feature(generic_associated_types)]
+#![cfg_attr(test, feature(test))]
 
+mod arithmetic;
 pub mod array;
+pub mod array_iters;
+pub mod einsum;
 mod formatter;
-mod shape_utils;
+mod gemm;
+pub mod linalg;
+pub mod manipulation;
+mod matmul;
+pub mod reductions;
+pub mod shape_utils;
+pub mod slice;
 mod storage;
+pub mod tensor_products;
+#[cfg(test)]
+pub(crate) mod test_utils;
 mod thread_safe_storage;
 mod thread_unsafe_storage;
 mod utils;
 mod view_iters;
 mod views;
+
+pub use storage::Storage;
+pub use thread_safe_storage::ThreadSafeStorage;
+pub use thread_unsafe_storage::ThreadUnsafeStorage;
+pub use views::{ArrayView, ContiguousView, StridedView};
@@ -0,0 +1,159 @@
+use num_traits::Float;
+
+use super::{to_array, LinearSystems, Matrices};
+use crate::array::Array;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView};
+
+/// Lower triangular factor `L` of an `n x n` row-major symmetric matrix, such
+/// that `A = L L^T`. Only the lower triangle of `a` is read.
+pub(crate) fn cholesky_factor<T: Float>(a: &[T], n: usize) -> Result<Vec<T>, String> {
+    let mut l = vec![T::zero(); n * n];
+    for j in 0..n {
+        let diag = (0..j).fold(a[j * n + j], |acc, p| acc - l[j * n + p] * l[j * n + p]);
+        if diag <= T::zero() || diag.is_nan() {
+            return Err("cholesky: matrix is not positive definite".to_string());
+        }
+        let diag = diag.sqrt();
+        l[j * n + j] = diag;
+        for i in j + 1..n {
+            let value = (0..j).fold(a[i * n + j], |acc, p| acc - l[i * n + p] * l[j * n + p]);
+            l[i * n + j] = value / diag;
+        }
+    }
+    Ok(l)
+}
+
+/// Solves `L L^T X = B` for an `n x k` row-major right-hand side.
+pub(crate) fn cholesky_solve<T: Float>(l: &[T], n: usize, b: &[T], k: usize) -> Vec<T> {
+    let mut x = b.to_vec();
+    for i in 0..n {
+        for p in 0..i {
+            let factor = l[i * n + p];
+            for j in 0..k {
+                x[i * k + j] = x[i * k + j] - factor * x[p * k + j];
+            }
+        }
+        for j in 0..k {
+            x[i * k + j] = x[i * k + j] / l[i * n + i];
+        }
+    }
+    for i in (0..n).rev() {
+        for p in i + 1..n {
+            let factor = l[p * n + i];
+            for j in 0..k {
+                x[i * k + j] = x[i * k + j] - factor * x[p * k + j];
+            }
+        }
+        for j in 0..k {
+            x[i * k + j] = x[i * k + j] / l[i * n + i];
+        }
+    }
+    x
+}
+
+/// Cholesky factorisation `A = L L^T` of each symmetric positive definite
+/// matrix in the batch, returning the lower triangular factor `L`. Only the
+/// lower triangle of `a` is read.
+pub fn cholesky<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_square_array(a)?;
+    let n = a.rows;
+    let mut data = Vec::with_capacity(a.data.len());
+    for matrix in a.iter() {
+        data.extend(cholesky_factor(matrix, n)?);
+    }
+    Ok(to_array(&a.batch, &[n, n], data))
+}
+
+/// Solves `A X = B` given the lower triangular Cholesky factor `l` of `A`, as
+/// returned by [`cholesky`]. `b` follows the same rules as in
+/// [`solve`](super::solve).
+pub fn cho_solve<T, StorageType, LhsView, RhsView>(
+    l: &Array<StorageType, LhsView>,
+    b: &Array<StorageType, RhsView>,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    LhsView: ArrayView,
+    RhsView: ArrayView,
+{
+    let systems = LinearSystems::new(l, b, "cho_solve")?;
+    let (n, k) = (systems.lhs.rows, systems.rhs.cols);
+    let mut data = Vec::with_capacity(systems.rhs.data.len());
+    for (l, b) in systems.lhs.iter().zip(systems.rhs.iter()) {
+        if (0..n).any(|i| l[i * n + i] == T::zero()) {
+            return Err("cho_solve: factor is singular".to_string());
+        }
+        data.extend(cholesky_solve(l, n, b, k));
+    }
+    Ok(systems.solutions_to_array(data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{array, assert_close};
+    use crate::thread_unsafe_storage::ThreadUnsafeStorage;
+
+    #[test]
+    fn cholesky_known_factor() {
+        let a = array(
+            &[4.0, 12.0, -16.0, 12.0, 37.0, -43.0, -16.0, -43.0, 98.0],
+            &[3, 3],
+        );
+        let l = cholesky(&a).unwrap();
+        assert_close(&l, &[2.0, 0.0, 0.0, 6.0, 1.0, 0.0, -8.0, 5.0, 3.0], 1e-12);
+        assert_close(&l.matmul(&l.t()).unwrap(), &a.to_vec(), 1e-12);
+    }
+
+    #[test]
+    fn cholesky_thread_unsafe_batched() {
+        let a = Array::from_parts(
+            ThreadUnsafeStorage::from(vec![2.0f32, 1.0, 1.0, 2.0, 9.0, 0.0, 0.0, 1.0]),
+            ContiguousView::new([2, 2, 2]),
+        );
+        let l = cholesky(&a).unwrap();
+        assert_eq!(l.shape(), &[2, 2, 2]);
+        let reconstructed = l.matmul(&l.permute_axes([0, 2, 1])).unwrap().to_vec();
+        for (x, y) in reconstructed.iter().zip(a.to_vec().iter()) {
+            assert!((x - y).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn cholesky_not_positive_definite() {
+        assert!(cholesky(&array(&[1.0, 2.0, 2.0, 1.0], &[2, 2])).is_err());
+        assert!(cholesky(&array(&[0.0, 0.0, 0.0, 1.0], &[2, 2])).is_err());
+        assert!(cholesky(&array(&[f64::NAN, 0.0, 0.0, 1.0], &[2, 2])).is_err());
+        assert!(cholesky(&array(&[1.0, 2.0], &[1, 2])).is_err());
+    }
+
+    #[test]
+    fn cho_solve_matches_solve() {
+        let a = array(&[4.0, 2.0, 0.6, 2.0, 5.0, 1.0, 0.6, 1.0, 3.0], &[3, 3]);
+        let b = array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 2]);
+        let l = cholesky(&a).unwrap();
+        let x = cho_solve(&l, &b).unwrap();
+        assert_close(&x, &crate::linalg::solve(&a, &b).unwrap().to_vec(), 1e-12);
+
+        let v = cho_solve(&l, &b.slice(crate::s![.., 0])).unwrap();
+        assert_eq!(v.shape(), &[3]);
+        assert_close(&a.matmul(&v).unwrap(), &[1.0, 3.0, 5.0], 1e-12);
+    }
+
+    #[test]
+    fn cho_solve_errors() {
+        let l = array(&[1.0, 0.0, 1.0, 0.0], &[2, 2]);
+        assert!(cho_solve(&l, &array(&[1.0, 2.0], &[2])).is_err());
+        let l = array(&[1.0, 0.0, 1.0, 1.0], &[2, 2]);
+        assert!(cho_solve(&l, &array(&[1.0, 2.0, 3.0], &[3])).is_err());
+    }
+}
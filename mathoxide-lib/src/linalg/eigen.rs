@@ -0,0 +1,447 @@
+use num_complex::Complex;
+use num_traits::Float;
+
+use super::{norm2, to_array, Matrices};
+use crate::array::Array;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView};
+
+/// Upper bound on the number of Jacobi sweeps in [`eigh`]. Convergence is
+/// quadratic, so this is only reached for matrices containing NaN or
+/// infinities.
+const MAX_SWEEPS: usize = 64;
+
+/// Number of QR iterations allowed per eigenvalue in [`eig`].
+const MAX_ITERATIONS: usize = 30;
+
+/// Eigen decomposition of an `n x n` row-major symmetric matrix using cyclic
+/// Jacobi rotations. Only the lower triangle of `a` is read. Returns the
+/// eigenvalues in ascending order and the row-major matrix whose columns are
+/// the matching orthonormal eigenvectors.
+pub(crate) fn symmetric_eigen<T: Float>(a: &[T], n: usize) -> (Vec<T>, Vec<T>) {
+    let mut a = (0..n * n)
+        .map(|idx| {
+            let (i, j) = (idx / n, idx % n);
+            a[i.max(j) * n + i.min(j)]
+        })
+        .collect::<Vec<T>>();
+    let mut v = vec![T::zero(); n * n];
+    for i in 0..n {
+        v[i * n + i] = T::one();
+    }
+
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[p * n + q];
+                let (app, aqq) = (a[p * n + p], a[q * n + q]);
+                if apq == T::zero() || apq.abs() <= T::epsilon() * (app * aqq).abs().sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                let theta = (aqq - app) / (apq + apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + T::one()).sqrt());
+                let c = T::one() / (t * t + T::one()).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                    a[k * n + p] = c * akp - s * akq;
+                    a[k * n + q] = s * akp + c * akq;
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[p * n + k], a[q * n + k]);
+                    a[p * n + k] = c * apk - s * aqk;
+                    a[q * n + k] = s * apk + c * aqk;
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let mut order = (0..n).collect::<Vec<usize>>();
+    order.sort_by(|&i, &j| {
+        a[i * n + i]
+            .partial_cmp(&a[j * n + j])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let w = order.iter().map(|&j| a[j * n + j]).collect();
+    let vectors = (0..n * n)
+        .map(|idx| v[(idx / n) * n + order[idx % n]])
+        .collect();
+    (w, vectors)
+}
+
+fn abs1<T: Float>(z: Complex<T>) -> T {
+    z.re.abs() + z.im.abs()
+}
+
+/// A unitary plane rotation `G = [[conj(c), conj(s)], [-s, c]]` chosen so
+/// that `G [x, y]^T = [r, 0]^T`.
+#[derive(Clone, Copy)]
+struct Givens<T> {
+    c: Complex<T>,
+    s: Complex<T>,
+}
+
+impl<T: Float> Givens<T> {
+    fn new(x: Complex<T>, y: Complex<T>) -> Self {
+        let r = norm2([x.re, x.im, y.re, y.im]);
+        if r == T::zero() {
+            Self {
+                c: Complex::new(T::one(), T::zero()),
+                s: Complex::new(T::zero(), T::zero()),
+            }
+        } else {
+            Self { c: x / r, s: y / r }
+        }
+    }
+
+    /// Replaces rows `p` and `q` of `a` by `G` applied to them, for the
+    /// columns in `cols`.
+    fn rotate_rows(
+        &self,
+        a: &mut [Complex<T>],
+        n: usize,
+        p: usize,
+        q: usize,
+        cols: std::ops::Range<usize>,
+    ) {
+        for j in cols {
+            let (x, y) = (a[p * n + j], a[q * n + j]);
+            a[p * n + j] = self.c.conj() * x + self.s.conj() * y;
+            a[q * n + j] = self.c * y - self.s * x;
+        }
+    }
+
+    /// Multiplies columns `p` and `q` of `a` by `G^H` from the right, for the
+    /// rows in `rows`.
+    fn rotate_cols(
+        &self,
+        a: &mut [Complex<T>],
+        n: usize,
+        p: usize,
+        q: usize,
+        rows: std::ops::Range<usize>,
+    ) {
+        for i in rows {
+            let (x, y) = (a[i * n + p], a[i * n + q]);
+            a[i * n + p] = x * self.c + y * self.s;
+            a[i * n + q] = y * self.c.conj() - x * self.s.conj();
+        }
+    }
+}
+
+/// Eigenvalues and row-major eigenvector matrix of a general matrix.
+type ComplexEigen<T> = (Vec<Complex<T>>, Vec<Complex<T>>);
+
+/// Eigenvalues and unit-norm eigenvectors of an `n x n` row-major matrix.
+///
+/// The matrix is reduced to upper Hessenberg form and then to a complex Schur
+/// form `A = Z T Z^H` by shifted QR iterations. The eigenvectors of the upper
+/// triangular `T` are found by back substitution and mapped back through `Z`.
+pub(crate) fn general_eigen<T: Float>(a: &[T], n: usize) -> Result<ComplexEigen<T>, String> {
+    let zero = Complex::new(T::zero(), T::zero());
+    let mut h = a
+        .iter()
+        .map(|&x| Complex::new(x, T::zero()))
+        .collect::<Vec<Complex<T>>>();
+    let mut z = vec![zero; n * n];
+    for i in 0..n {
+        z[i * n + i] = Complex::new(T::one(), T::zero());
+    }
+    let scale = norm2(a.iter().copied());
+
+    // Hessenberg reduction.
+    for k in 0..n.saturating_sub(2) {
+        for i in k + 2..n {
+            if h[i * n + k] == zero {
+                continue;
+            }
+            let g = Givens::new(h[(k + 1) * n + k], h[i * n + k]);
+            g.rotate_rows(&mut h, n, k + 1, i, k..n);
+            g.rotate_cols(&mut h, n, k + 1, i, 0..n);
+            g.rotate_cols(&mut z, n, k + 1, i, 0..n);
+            h[i * n + k] = zero;
+        }
+    }
+
+    // Shifted QR iterations on the active window `lo..=hi`, deflating
+    // eigenvalues from the bottom as the subdiagonal vanishes.
+    let mut hi = n.saturating_sub(1);
+    let mut iterations = 0;
+    while hi > 0 {
+        let mut lo = hi;
+        while lo > 0 {
+            let mut reference = abs1(h[lo * n + lo]) + abs1(h[(lo - 1) * n + lo - 1]);
+            if reference == T::zero() {
+                reference = scale;
+            }
+            if abs1(h[lo * n + lo - 1]) <= T::epsilon() * reference {
+                h[lo * n + lo - 1] = zero;
+                break;
+            }
+            lo -= 1;
+        }
+        if lo == hi {
+            hi -= 1;
+            iterations = 0;
+            continue;
+        }
+
+        iterations += 1;
+        if iterations > MAX_ITERATIONS * n {
+            return Err("eig: QR iteration failed to converge".to_string());
+        }
+        let shift = if iterations % 10 == 0 {
+            // Exceptional shift to break cycles.
+            h[hi * n + hi] + Complex::new(abs1(h[hi * n + hi - 1]), T::zero())
+        } else {
+            wilkinson_shift(
+                h[(hi - 1) * n + hi - 1],
+                h[(hi - 1) * n + hi],
+                h[hi * n + hi - 1],
+                h[hi * n + hi],
+            )
+        };
+
+        for k in lo..=hi {
+            h[k * n + k] = h[k * n + k] - shift;
+        }
+        let mut rotations = Vec::with_capacity(hi - lo);
+        for k in lo..hi {
+            let g = Givens::new(h[k * n + k], h[(k + 1) * n + k]);
+            g.rotate_rows(&mut h, n, k, k + 1, k..n);
+            h[(k + 1) * n + k] = zero;
+            rotations.push(g);
+        }
+        for (k, g) in (lo..hi).zip(rotations) {
+            g.rotate_cols(&mut h, n, k, k + 1, 0..(k + 2).min(hi + 1));
+            g.rotate_cols(&mut z, n, k, k + 1, 0..n);
+        }
+        for k in lo..=hi {
+            h[k * n + k] = h[k * n + k] + shift;
+        }
+    }
+
+    let w = (0..n).map(|i| h[i * n + i]).collect::<Vec<Complex<T>>>();
+    let smallest = T::epsilon() * scale.max(T::min_positive_value());
+    let mut vectors = vec![zero; n * n];
+    let mut y = vec![zero; n];
+    for k in 0..n {
+        y.iter_mut().for_each(|x| *x = zero);
+        y[k] = Complex::new(T::one(), T::zero());
+        for i in (0..k).rev() {
+            let sum = (i + 1..=k).fold(zero, |acc, j| acc + h[i * n + j] * y[j]);
+            let mut denominator = h[i * n + i] - w[k];
+            if abs1(denominator) < smallest {
+                denominator = Complex::new(smallest, T::zero());
+            }
+            y[i] = -sum / denominator;
+        }
+        let column = (0..n)
+            .map(|i| (0..=k).fold(zero, |acc, j| acc + z[i * n + j] * y[j]))
+            .collect::<Vec<Complex<T>>>();
+        let norm = norm2(column.iter().flat_map(|x| [x.re, x.im]));
+        for (i, value) in column.into_iter().enumerate() {
+            vectors[i * n + k] = value / norm;
+        }
+    }
+    Ok((w, vectors))
+}
+
+/// Eigenvalue of the `2 x 2` matrix `[[a, b], [c, d]]` closest to `d`.
+fn wilkinson_shift<T: Float>(
+    a: Complex<T>,
+    b: Complex<T>,
+    c: Complex<T>,
+    d: Complex<T>,
+) -> Complex<T> {
+    let two = T::one() + T::one();
+    let half_difference = (a - d) / two;
+    let discriminant = (half_difference * half_difference + b * c).sqrt();
+    let mean = (a + d) / two;
+    let (first, second) = (mean + discriminant, mean - discriminant);
+    if abs1(first - d) <= abs1(second - d) {
+        first
+    } else {
+        second
+    }
+}
+
+type EigenArrays<StorageType> = (
+    Array<StorageType, ContiguousView>,
+    Array<StorageType, ContiguousView>,
+);
+
+/// Eigenvalues and eigenvectors of each symmetric matrix in the batch. Only
+/// the lower triangle of `a` is read.
+///
+/// The eigenvalues are returned in ascending order, and column `i` of the
+/// eigenvector matrix is the unit eigenvector of eigenvalue `i`.
+pub fn eigh<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+) -> Result<EigenArrays<StorageType>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_square_array(a)?;
+    let n = a.rows;
+    let mut w = Vec::with_capacity(a.len() * n);
+    let mut v = Vec::with_capacity(a.data.len());
+    for matrix in a.iter() {
+        let (values, vectors) = symmetric_eigen(matrix, n);
+        w.extend(values);
+        v.extend(vectors);
+    }
+    Ok((to_array(&a.batch, &[n], w), to_array(&a.batch, &[n, n], v)))
+}
+
+/// Eigenvalues and right eigenvectors of each general real matrix in the
+/// batch. Both are returned as complex numbers, and column `i` of the
+/// eigenvector matrix is the unit eigenvector of eigenvalue `i`. The
+/// eigenvalues are not ordered.
+pub fn eig<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+) -> Result<EigenArrays<StorageType::Rebind<Complex<T>>>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_square_array(a)?;
+    let n = a.rows;
+    let mut w = Vec::with_capacity(a.len() * n);
+    let mut v = Vec::with_capacity(a.data.len());
+    for matrix in a.iter() {
+        let (values, vectors) = general_eigen(matrix, n)?;
+        w.extend(values);
+        v.extend(vectors);
+    }
+    Ok((to_array(&a.batch, &[n], w), to_array(&a.batch, &[n, n], v)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{array, assert_close};
+
+    /// Checks `A V = V diag(w)` for each matrix in a batch of `n x n`
+    /// matrices and that the eigenvectors have unit norm.
+    fn assert_eigenpairs(a: &[f64], w: &[Complex<f64>], v: &[Complex<f64>], n: usize, tol: f64) {
+        for ((a, w), v) in a.chunks(n * n).zip(w.chunks(n)).zip(v.chunks(n * n)) {
+            for k in 0..n {
+                let norm = (0..n).map(|i| v[i * n + k].norm_sqr()).sum::<f64>();
+                assert!(
+                    (norm - 1.0).abs() < tol,
+                    "eigenvector {} has norm {}",
+                    k,
+                    norm
+                );
+                for i in 0..n {
+                    let av = (0..n).fold(Complex::new(0.0, 0.0), |acc, j| {
+                        acc + v[j * n + k] * a[i * n + j]
+                    });
+                    let residual = (av - v[i * n + k] * w[k]).norm();
+                    assert!(
+                        residual < tol,
+                        "A v != w v for eigenvalue {}: {:?}",
+                        w[k],
+                        residual
+                    );
+                }
+            }
+        }
+    }
+
+    fn real(values: &[f64]) -> Vec<Complex<f64>> {
+        values.iter().map(|&x| Complex::new(x, 0.0)).collect()
+    }
+
+    #[test]
+    fn eigh_sorted_and_orthonormal() {
+        let data = [2.0, -1.0, 0.0, -1.0, 2.0, -1.0, 0.0, -1.0, 2.0];
+        let a = array(&data, &[3, 3]);
+        let (w, v) = eigh(&a).unwrap();
+        let sqrt2 = 2f64.sqrt();
+        assert_close(&w, &[2.0 - sqrt2, 2.0, 2.0 + sqrt2], 1e-12);
+        assert_close(
+            &v.t().matmul(&v).unwrap(),
+            &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            1e-12,
+        );
+        assert_eigenpairs(&data, &real(&w.to_vec()), &real(&v.to_vec()), 3, 1e-12);
+    }
+
+    #[test]
+    fn eigh_reads_lower_triangle_batched() {
+        let a = array(&[1.0, 100.0, 2.0, 1.0, 5.0, 0.0, 0.0, 3.0], &[2, 2, 2]);
+        let (w, v) = eigh(&a).unwrap();
+        assert_close(&w, &[-1.0, 3.0, 3.0, 5.0], 1e-12);
+        let symmetric = [1.0, 2.0, 2.0, 1.0, 5.0, 0.0, 0.0, 3.0];
+        assert_eigenpairs(&symmetric, &real(&w.to_vec()), &real(&v.to_vec()), 2, 1e-12);
+    }
+
+    #[test]
+    fn eig_complex_pair() {
+        let data = [0.0, -1.0, 1.0, 0.0];
+        let (w, v) = eig(&array(&data, &[2, 2])).unwrap();
+        let mut values = w.to_vec();
+        values.sort_by(|x, y| x.im.partial_cmp(&y.im).unwrap());
+        assert!((values[0] - Complex::new(0.0, -1.0)).norm() < 1e-12);
+        assert!((values[1] - Complex::new(0.0, 1.0)).norm() < 1e-12);
+        assert_eigenpairs(&data, &w.to_vec(), &v.to_vec(), 2, 1e-12);
+    }
+
+    #[test]
+    fn eig_general_batched() {
+        let data = [
+            4.0, 1.0, -2.0, 2.0, //
+            1.0, 2.0, 0.0, 1.0, //
+            -2.0, 0.0, 3.0, -2.0, //
+            2.0, 1.0, -2.0, -1.0, //
+            1.0, 2.0, 3.0, 4.0, //
+            0.0, 1.0, 5.0, 6.0, //
+            0.0, 0.0, 1.0, 7.0, //
+            -3.0, 0.0, 0.0, 1.0, //
+        ];
+        let (w, v) = eig(&array(&data, &[2, 4, 4])).unwrap();
+        assert_eq!(w.shape(), &[2, 4]);
+        assert_eq!(v.shape(), &[2, 4, 4]);
+        assert_eigenpairs(&data, &w.to_vec(), &v.to_vec(), 4, 1e-10);
+
+        // The eigenvalues of a real matrix come in conjugate pairs, and their
+        // sum is the trace.
+        let w = w.to_vec();
+        let trace = w[4..]
+            .iter()
+            .fold(Complex::new(0.0, 0.0), |acc, &x| acc + x);
+        assert!((trace - Complex::new(4.0, 0.0)).norm() < 1e-10);
+        for x in &w[4..] {
+            assert!(w[4..].iter().any(|y| (x.conj() - y).norm() < 1e-8));
+        }
+    }
+
+    #[test]
+    fn eig_defective_and_triangular() {
+        let data = [1.0, 1.0, 0.0, 1.0];
+        let (w, v) = eig(&array(&data, &[2, 2])).unwrap();
+        assert!(w
+            .to_vec()
+            .iter()
+            .all(|x| (x - Complex::new(1.0, 0.0)).norm() < 1e-12));
+        assert_eigenpairs(&data, &w.to_vec(), &v.to_vec(), 2, 1e-12);
+
+        let data = [3.0, 1.0, 2.0, 0.0, 2.0, 5.0, 0.0, 0.0, 1.0];
+        let (w, v) = eig(&array(&data, &[3, 3])).unwrap();
+        assert_eigenpairs(&data, &w.to_vec(), &v.to_vec(), 3, 1e-12);
+    }
+}
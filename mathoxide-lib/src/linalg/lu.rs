@@ -0,0 +1,298 @@
+use num_traits::Float;
+
+use super::{to_array, LinearSystems, Matrices};
+use crate::array::Array;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView};
+
+/// In-place LU factorisation of an `n x n` row-major matrix with partial
+/// pivoting.
+///
+/// On return `a` holds `U` on and above the diagonal and the multipliers of
+/// the unit lower triangular `L` below it, such that `P A = L U` where row
+/// `i` of `P A` is row `perm[i]` of `A`.
+pub(crate) struct LuFactor<T> {
+    pub n: usize,
+    pub lu: Vec<T>,
+    pub perm: Vec<usize>,
+    pub swaps: usize,
+    pub singular: bool,
+}
+
+impl<T: Float> LuFactor<T> {
+    pub fn new(a: &[T], n: usize) -> Self {
+        let mut lu = a.to_vec();
+        let mut perm = (0..n).collect::<Vec<usize>>();
+        let mut swaps = 0;
+        let mut singular = false;
+
+        for col in 0..n {
+            let pivot = (col..n)
+                .max_by(|&i, &j| {
+                    lu[i * n + col]
+                        .abs()
+                        .partial_cmp(&lu[j * n + col].abs())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap();
+            if pivot != col {
+                for j in 0..n {
+                    lu.swap(col * n + j, pivot * n + j);
+                }
+                perm.swap(col, pivot);
+                swaps += 1;
+            }
+
+            let diag = lu[col * n + col];
+            if diag == T::zero() {
+                singular = true;
+                continue;
+            }
+            for row in col + 1..n {
+                let factor = lu[row * n + col] / diag;
+                lu[row * n + col] = factor;
+                for j in col + 1..n {
+                    lu[row * n + j] = lu[row * n + j] - factor * lu[col * n + j];
+                }
+            }
+        }
+
+        Self {
+            n,
+            lu,
+            perm,
+            swaps,
+            singular,
+        }
+    }
+
+    pub fn det(&self) -> T {
+        let n = self.n;
+        let product = (0..n).fold(T::one(), |acc, i| acc * self.lu[i * n + i]);
+        if self.swaps % 2 == 1 {
+            -product
+        } else {
+            product
+        }
+    }
+
+    /// Solves `A X = B` for an `n x k` row-major right-hand side.
+    pub fn solve(&self, b: &[T], k: usize) -> Vec<T> {
+        let n = self.n;
+        let mut x = Vec::with_capacity(n * k);
+        for &row in &self.perm {
+            x.extend_from_slice(&b[row * k..(row + 1) * k]);
+        }
+        for i in 0..n {
+            for p in 0..i {
+                let factor = self.lu[i * n + p];
+                for j in 0..k {
+                    x[i * k + j] = x[i * k + j] - factor * x[p * k + j];
+                }
+            }
+        }
+        for i in (0..n).rev() {
+            for p in i + 1..n {
+                let factor = self.lu[i * n + p];
+                for j in 0..k {
+                    x[i * k + j] = x[i * k + j] - factor * x[p * k + j];
+                }
+            }
+            let diag = self.lu[i * n + i];
+            for j in 0..k {
+                x[i * k + j] = x[i * k + j] / diag;
+            }
+        }
+        x
+    }
+}
+
+type Lu<StorageType> = (
+    Array<StorageType, ContiguousView>,
+    Array<StorageType, ContiguousView>,
+    Array<StorageType, ContiguousView>,
+);
+
+/// LU decomposition with partial pivoting, returning `(P, L, U)` such that
+/// `A = P L U`, with `L` unit lower triangular and `U` upper triangular.
+pub fn lu<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+) -> Result<Lu<StorageType>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_square_array(a)?;
+    let n = a.rows;
+    let mut p = Vec::with_capacity(a.data.len());
+    let mut l = Vec::with_capacity(a.data.len());
+    let mut u = Vec::with_capacity(a.data.len());
+
+    for matrix in a.iter() {
+        let factor = LuFactor::new(matrix, n);
+        let start = p.len();
+        p.resize(start + n * n, T::zero());
+        for (i, &row) in factor.perm.iter().enumerate() {
+            p[start + row * n + i] = T::one();
+        }
+        for i in 0..n {
+            for j in 0..n {
+                let value = factor.lu[i * n + j];
+                l.push(match i.cmp(&j) {
+                    std::cmp::Ordering::Greater => value,
+                    std::cmp::Ordering::Equal => T::one(),
+                    std::cmp::Ordering::Less => T::zero(),
+                });
+                u.push(if i <= j { value } else { T::zero() });
+            }
+        }
+    }
+
+    Ok((
+        to_array(&a.batch, &[n, n], p),
+        to_array(&a.batch, &[n, n], l),
+        to_array(&a.batch, &[n, n], u),
+    ))
+}
+
+/// Determinant of each matrix in the batch.
+pub fn det<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_square_array(a)?;
+    let data = a
+        .iter()
+        .map(|matrix| LuFactor::new(matrix, a.rows).det())
+        .collect();
+    Ok(to_array(&a.batch, &[], data))
+}
+
+/// Solves `A X = B`. `b` is either a stack of `n x k` matrices whose batch
+/// dimensions broadcast against those of `a`, or a single vector of length
+/// `n`, in which case the solution is a vector as well.
+pub fn solve<T, StorageType, LhsView, RhsView>(
+    a: &Array<StorageType, LhsView>,
+    b: &Array<StorageType, RhsView>,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    LhsView: ArrayView,
+    RhsView: ArrayView,
+{
+    let systems = LinearSystems::new(a, b, "solve")?;
+    let (n, k) = (systems.lhs.rows, systems.rhs.cols);
+    let mut data = Vec::with_capacity(systems.rhs.data.len());
+    for (a, b) in systems.lhs.iter().zip(systems.rhs.iter()) {
+        let factor = LuFactor::new(a, n);
+        if factor.singular {
+            return Err("solve: matrix is singular".to_string());
+        }
+        data.extend(factor.solve(b, k));
+    }
+    Ok(systems.solutions_to_array(data))
+}
+
+/// Inverse of each matrix in the batch.
+pub fn inv<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_square_array(a)?;
+    let n = a.rows;
+    let mut identity = vec![T::zero(); n * n];
+    for i in 0..n {
+        identity[i * n + i] = T::one();
+    }
+
+    let mut data = Vec::with_capacity(a.data.len());
+    for matrix in a.iter() {
+        let factor = LuFactor::new(matrix, n);
+        if factor.singular {
+            return Err("inv: matrix is singular".to_string());
+        }
+        data.extend(factor.solve(&identity, n));
+    }
+    Ok(to_array(&a.batch, &[n, n], data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{array, assert_close};
+
+    #[test]
+    fn lu_reconstructs() {
+        let a = array(&[0.0, 2.0, 1.0, 1.0, 1.0, 0.0, 3.0, 4.0, 5.0], &[3, 3]);
+        let (p, l, u) = lu(&a).unwrap();
+        assert_close(&l.slice(crate::s![0, 1..]), &[0.0, 0.0], 0.0);
+        assert_close(&u.slice(crate::s![2, ..2]), &[0.0, 0.0], 0.0);
+        let reconstructed = p.matmul(&l.matmul(&u).unwrap()).unwrap();
+        assert_close(&reconstructed, &a.to_vec(), 1e-12);
+    }
+
+    #[test]
+    fn det_batched() {
+        let a = array(
+            &[1.0, 2.0, 3.0, 4.0, 2.0, 0.0, 0.0, 3.0, 1.0, 2.0, 2.0, 4.0],
+            &[3, 2, 2],
+        );
+        let d = det(&a).unwrap();
+        assert_eq!(d.shape(), &[3]);
+        assert_close(&d, &[-2.0, 6.0, 0.0], 1e-12);
+        assert!(det(&array(&[1.0, 2.0], &[1, 2])).is_err());
+    }
+
+    #[test]
+    fn solve_vector_and_matrix() {
+        let a = array(&[3.0, 1.0, 1.0, 2.0], &[2, 2]);
+        let b = array(&[9.0, 8.0], &[2]);
+        let x = solve(&a, &b).unwrap();
+        assert_eq!(x.shape(), &[2]);
+        assert_close(&x, &[2.0, 3.0], 1e-12);
+
+        let b = array(&[9.0, 1.0, 8.0, 2.0], &[2, 2]);
+        let x = solve(&a.t(), &b).unwrap();
+        assert_close(&a.t().matmul(&x).unwrap(), &b.to_vec(), 1e-12);
+    }
+
+    #[test]
+    fn solve_broadcast_batch() {
+        let a = array(&[2.0, 0.0, 0.0, 4.0, 1.0, 1.0, 0.0, 1.0], &[2, 2, 2]);
+        let b = array(&[2.0, 4.0], &[2, 1]);
+        let x = solve(&a, &b).unwrap();
+        assert_eq!(x.shape(), &[2, 2, 1]);
+        assert_close(&x, &[1.0, 1.0, -2.0, 4.0], 1e-12);
+    }
+
+    #[test]
+    fn inv_roundtrip() {
+        let a = array(&[4.0, 7.0, 2.0, 6.0, 1.0, 0.0, 0.0, 1.0], &[2, 2, 2]);
+        let a_inv = inv(&a).unwrap();
+        assert_close(&a_inv.slice(crate::s![0]), &[0.6, -0.7, -0.2, 0.4], 1e-12);
+        assert_close(
+            &a.matmul(&a_inv).unwrap(),
+            &[1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0],
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn singular_matrix_errors() {
+        let a = array(&[1.0, 2.0, 2.0, 4.0], &[2, 2]);
+        assert!(inv(&a).is_err());
+        assert!(solve(&a, &array(&[1.0, 2.0], &[2])).is_err());
+        assert_close(&det(&a).unwrap(), &[0.0], 0.0);
+        assert!(lu(&a).is_ok());
+    }
+}
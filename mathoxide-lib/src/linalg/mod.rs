@@ -0,0 +1,219 @@
+//! Dense linear algebra on stacks of matrices.
+//!
+//! Every routine accepts arrays with two or more dimensions, where the last
+//! two dimensions hold the matrices and any leading dimensions form a batch.
+
+use num_traits::{Float, Num};
+
+use crate::array::Array;
+use crate::shape_utils::broadcast_shapes;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView};
+
+mod cholesky;
+mod eigen;
+mod lu;
+mod norm;
+mod qr;
+mod svd;
+
+pub use cholesky::{cho_solve, cholesky};
+pub use eigen::{eig, eigh};
+pub use lu::{det, inv, lu, solve};
+pub use norm::{norm, NormOrder};
+pub use qr::{lstsq, qr, QrMode};
+pub use svd::{cond, matrix_rank, pinv, svd};
+
+/// Splits a shape into its batch dimensions and the matrix dimensions.
+pub(crate) fn split_matrix_shape(shape: &[usize]) -> Result<(&[usize], usize, usize), String> {
+    if shape.len() < 2 {
+        return Err(format!(
+            "expected an array with at least 2 dimensions, got shape {:?}",
+            shape
+        ));
+    }
+    let (batch, matrix) = shape.split_at(shape.len() - 2);
+    Ok((batch, matrix[0], matrix[1]))
+}
+
+/// Like [`split_matrix_shape`], but also requires the matrices to be square.
+pub(crate) fn split_square_shape(shape: &[usize]) -> Result<(&[usize], usize), String> {
+    let (batch, rows, cols) = split_matrix_shape(shape)?;
+    if rows != cols {
+        return Err(format!("expected square matrices, got shape {:?}", shape));
+    }
+    Ok((batch, rows))
+}
+
+/// A batch of matrices copied out of an array, stored one after the other in
+/// row-major order.
+pub(crate) struct Matrices<T> {
+    pub batch: Vec<usize>,
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<T>,
+}
+
+impl<T: Float> Matrices<T> {
+    pub fn from_array<StorageType, ViewType>(
+        array: &Array<StorageType, ViewType>,
+    ) -> Result<Self, String>
+    where
+        StorageType: Storage<Stored = T>,
+        ViewType: ArrayView,
+    {
+        let (batch, rows, cols) = split_matrix_shape(array.shape())?;
+        Ok(Self {
+            batch: batch.to_vec(),
+            rows,
+            cols,
+            data: array.to_vec(),
+        })
+    }
+
+    pub fn from_square_array<StorageType, ViewType>(
+        array: &Array<StorageType, ViewType>,
+    ) -> Result<Self, String>
+    where
+        StorageType: Storage<Stored = T>,
+        ViewType: ArrayView,
+    {
+        split_square_shape(array.shape())?;
+        Self::from_array(array)
+    }
+
+    pub fn len(&self) -> usize {
+        self.batch.iter().product()
+    }
+
+    pub fn matrix(&self, idx: usize) -> &[T] {
+        let size = self.rows * self.cols;
+        &self.data[idx * size..(idx + 1) * size]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.len()).map(move |idx| self.matrix(idx))
+    }
+}
+
+/// A batch of square systems `A X = B`, with the batch dimensions of `A` and
+/// `B` broadcast against each other. `B` is either a stack of `n x k`
+/// matrices or a single vector of length `n`, which is solved as an `n x 1`
+/// matrix and turned back into a vector in the solution.
+pub(crate) struct LinearSystems<T> {
+    pub lhs: Matrices<T>,
+    pub rhs: Matrices<T>,
+    vector: bool,
+}
+
+impl<T: Float> LinearSystems<T> {
+    pub fn new<StorageType, LhsView, RhsView>(
+        a: &Array<StorageType, LhsView>,
+        b: &Array<StorageType, RhsView>,
+        name: &str,
+    ) -> Result<Self, String>
+    where
+        StorageType: Storage<Stored = T>,
+        LhsView: ArrayView,
+        RhsView: ArrayView,
+    {
+        let (a_batch, n) = split_square_shape(a.shape())?;
+        let vector = b.ndim() == 1;
+        let (b_batch, b_matrix) = match b.ndim() {
+            0 => {
+                return Err(format!(
+                    "{}: right-hand side must have at least 1 dimension",
+                    name
+                ))
+            }
+            1 => (&[][..], vec![b.shape()[0], 1]),
+            ndim => {
+                let (batch, matrix) = b.shape().split_at(ndim - 2);
+                (batch, matrix.to_vec())
+            }
+        };
+        if b_matrix[0] != n {
+            return Err(format!(
+                "{}: shapes {:?} and {:?} are not compatible",
+                name,
+                a.shape(),
+                b.shape()
+            ));
+        }
+
+        let batch = broadcast_shapes(&[a_batch, b_batch])?;
+        let rhs = Matrices::from_array(
+            &b.reshape([b_batch, &b_matrix[..]].concat())
+                .broadcast_to([&batch[..], &b_matrix[..]].concat()),
+        )?;
+        let lhs = Matrices::from_array(&a.broadcast_to([&batch[..], &[n, n]].concat()))?;
+        Ok(Self { lhs, rhs, vector })
+    }
+
+    /// Builds the solution array from the solutions of every system, stored
+    /// one after the other in row-major order.
+    pub fn solutions_to_array<StorageType>(
+        &self,
+        data: Vec<T>,
+    ) -> Array<StorageType, ContiguousView>
+    where
+        StorageType: Storage<Stored = T>,
+    {
+        if self.vector {
+            to_array(&self.rhs.batch, &[self.rhs.rows], data)
+        } else {
+            to_array(&self.rhs.batch, &[self.rhs.rows, self.rhs.cols], data)
+        }
+    }
+}
+
+/// Euclidean norm of `values`, accumulated as `scale * sqrt(ssq)` so that
+/// squaring neither overflows nor underflows.
+pub(crate) fn norm2<T: Float, I: IntoIterator<Item = T>>(values: I) -> T {
+    let mut scale = T::zero();
+    let mut ssq = T::one();
+    for value in values {
+        let value = value.abs();
+        if value.is_nan() {
+            return value;
+        }
+        if value > T::zero() {
+            if scale < value {
+                ssq = T::one() + ssq * (scale / value) * (scale / value);
+                scale = value;
+            } else {
+                ssq = ssq + (value / scale) * (value / scale);
+            }
+        }
+    }
+    scale * ssq.sqrt()
+}
+
+/// Builds a contiguous array of shape `batch + matrix` from row-major data.
+pub(crate) fn to_array<T, StorageType>(
+    batch: &[usize],
+    matrix: &[usize],
+    data: Vec<T>,
+) -> Array<StorageType, ContiguousView>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+{
+    let shape = [batch, matrix].concat();
+    debug_assert_eq!(shape.iter().product::<usize>(), data.len());
+    Array::from_parts(StorageType::from(data), ContiguousView::new(shape))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn norm2_is_scaled() {
+        assert_eq!(norm2(vec![3.0f64, 4.0]), 5.0);
+        assert_eq!(norm2(Vec::<f64>::new()), 0.0);
+        assert!((norm2(vec![3e200f64, 4e200]) / 5e200 - 1.0).abs() < 1e-15);
+        assert!((norm2(vec![3e-200f64, -4e-200]) / 5e-200 - 1.0).abs() < 1e-15);
+        assert!(norm2(vec![1.0f64, f64::NAN]).is_nan());
+    }
+}
@@ -0,0 +1,256 @@
+use num_traits::Float;
+
+use super::svd::Svd;
+use super::{norm2, to_array, Matrices};
+use crate::array::Array;
+use crate::reductions::fold_axes;
+use crate::shape_utils::normalize_axis;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView};
+
+/// Order of the norm computed by [`norm`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NormOrder {
+    /// Frobenius norm of a matrix, the 2-norm of its elements.
+    Fro,
+    /// Nuclear norm of a matrix, the sum of its singular values.
+    Nuc,
+    /// Largest absolute value of a vector, or largest absolute row sum of a
+    /// matrix.
+    Inf,
+    /// Smallest absolute value of a vector, or smallest absolute row sum of a
+    /// matrix.
+    NegInf,
+    /// `(sum |x|^p)^(1/p)` of a vector, with `P(0.0)` counting the non-zero
+    /// elements. For matrices only `1` and `-1` (largest and smallest
+    /// absolute column sum) and `2` and `-2` (largest and smallest singular
+    /// value) are supported.
+    P(f64),
+}
+
+fn vector_norm<T: Float>(values: &[T], ord: NormOrder) -> Result<T, String> {
+    let abs = values.iter().map(|x| x.abs());
+    Ok(match ord {
+        NormOrder::Inf => abs.fold(T::zero(), T::max),
+        NormOrder::NegInf => abs.fold(T::infinity(), T::min),
+        NormOrder::P(0.0) => T::from(values.iter().filter(|x| !x.is_zero()).count()).unwrap(),
+        NormOrder::P(1.0) => abs.fold(T::zero(), |acc, x| acc + x),
+        NormOrder::P(2.0) => norm2(values.iter().copied()),
+        NormOrder::P(p) if p > 0.0 => {
+            // Dividing by the largest value first keeps the powers in range.
+            let largest = abs.clone().fold(T::zero(), T::max);
+            if largest.is_zero() || largest.is_infinite() {
+                return Ok(largest);
+            }
+            let p = T::from(p).unwrap();
+            let sum = abs.fold(T::zero(), |acc, x| acc + (x / largest).powf(p));
+            largest * sum.powf(p.recip())
+        }
+        NormOrder::P(p) => {
+            let p = T::from(p).unwrap();
+            abs.fold(T::zero(), |acc, x| acc + x.powf(p))
+                .powf(p.recip())
+        }
+        NormOrder::Fro | NormOrder::Nuc => {
+            return Err(format!("norm: order {:?} is only valid for matrices", ord))
+        }
+    })
+}
+
+fn matrix_norm<T: Float>(a: &[T], m: usize, n: usize, ord: NormOrder) -> Result<T, String> {
+    let column_sums =
+        || (0..n).map(move |j| (0..m).fold(T::zero(), |acc, i| acc + a[i * n + j].abs()));
+    let row_sums =
+        || (0..m).map(move |i| (0..n).fold(T::zero(), |acc, j| acc + a[i * n + j].abs()));
+    let singular_values = || Svd::new(a, m, n, false).s;
+    Ok(match ord {
+        NormOrder::Fro => norm2(a.iter().copied()),
+        NormOrder::Nuc => singular_values()
+            .into_iter()
+            .fold(T::zero(), |acc, x| acc + x),
+        NormOrder::Inf => row_sums().fold(T::zero(), T::max),
+        NormOrder::NegInf => row_sums().fold(T::infinity(), T::min),
+        NormOrder::P(1.0) => column_sums().fold(T::zero(), T::max),
+        NormOrder::P(-1.0) => column_sums().fold(T::infinity(), T::min),
+        NormOrder::P(2.0) => singular_values().first().copied().unwrap_or_else(T::zero),
+        NormOrder::P(-2.0) => singular_values().last().copied().unwrap_or_else(T::zero),
+        NormOrder::P(p) => return Err(format!("norm: order {} is not supported for matrices", p)),
+    })
+}
+
+/// Vector or matrix norm with the semantics of NumPy's `linalg.norm`.
+///
+/// With a single `axis`, vector norms are taken along it. With two axes, the
+/// first indexes the rows and the second the columns of the matrices whose
+/// norms are taken. Without `axis`, a 1-D array is treated as a vector and a
+/// 2-D array as a matrix; if `ord` is also `None`, the 2-norm of all elements
+/// is returned regardless of the dimensions.
+///
+/// `ord` defaults to the 2-norm for vectors and the Frobenius norm for
+/// matrices, both computed with scaling so that squaring neither overflows
+/// nor underflows. With `keepdims`, the reduced axes are kept with length 1.
+pub fn norm<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+    ord: Option<NormOrder>,
+    axis: Option<&[isize]>,
+    keepdims: bool,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let axes = match (axis, ord) {
+        (None, None) => None,
+        (None, Some(_)) if a.ndim() <= 2 => Some((0..a.ndim()).collect::<Vec<usize>>()),
+        (None, Some(_)) => {
+            return Err(format!(
+                "norm: an order requires 1-D or 2-D input or explicit axes, got shape {:?}",
+                a.shape()
+            ))
+        }
+        (Some(axis), _) => {
+            let axes = axis
+                .iter()
+                .map(|&axis| normalize_axis(axis, a.ndim()))
+                .collect::<Result<Vec<usize>, String>>()?;
+            if axes.len() == 2 && axes[0] == axes[1] {
+                return Err(format!("norm: duplicate axes {:?}", axis));
+            }
+            Some(axes)
+        }
+    };
+
+    match axes.as_deref() {
+        None | Some([_]) => {
+            let axes = axes
+                .as_ref()
+                .map(|axes| axes.iter().map(|&axis| axis as isize).collect::<Vec<_>>());
+            let (shape, groups) =
+                fold_axes(a, axes.as_deref(), keepdims, Vec::new(), |acc, _, x| {
+                    acc.push(*x)
+                })?;
+            let data = groups
+                .iter()
+                .map(|values| vector_norm(values, ord.unwrap_or(NormOrder::P(2.0))))
+                .collect::<Result<Vec<T>, String>>()?;
+            Ok(to_array(&shape, &[], data))
+        }
+        Some(&[row_axis, col_axis]) => {
+            let mut order = (0..a.ndim())
+                .filter(|&axis| axis != row_axis && axis != col_axis)
+                .collect::<Vec<usize>>();
+            order.extend([row_axis, col_axis]);
+            let matrices = Matrices::from_array(&a.permute_axes(order))?;
+            let data = matrices
+                .iter()
+                .map(|matrix| {
+                    matrix_norm(
+                        matrix,
+                        matrices.rows,
+                        matrices.cols,
+                        ord.unwrap_or(NormOrder::Fro),
+                    )
+                })
+                .collect::<Result<Vec<T>, String>>()?;
+
+            let shape = if keepdims {
+                a.shape()
+                    .iter()
+                    .enumerate()
+                    .map(|(axis, &dim)| {
+                        if axis == row_axis || axis == col_axis {
+                            1
+                        } else {
+                            dim
+                        }
+                    })
+                    .collect()
+            } else {
+                matrices.batch.clone()
+            };
+            Ok(to_array(&shape, &[], data))
+        }
+        Some(axes) => Err(format!(
+            "norm: expected 1 or 2 axes, got {} for shape {:?}",
+            axes.len(),
+            a.shape()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{array, assert_close};
+
+    #[test]
+    fn vector_norms() {
+        let v = array(&[3.0, -4.0, 0.0], &[3]);
+        let norm_of = |ord| norm(&v, Some(ord), None, false).unwrap().to_vec()[0];
+        assert_eq!(norm(&v, None, None, false).unwrap().item(), 5.0);
+        assert_eq!(norm_of(NormOrder::P(1.0)), 7.0);
+        assert_eq!(norm_of(NormOrder::P(0.0)), 2.0);
+        assert_eq!(norm_of(NormOrder::Inf), 4.0);
+        assert_eq!(norm_of(NormOrder::NegInf), 0.0);
+        assert!((norm_of(NormOrder::P(3.0)) - 91f64.cbrt()).abs() < 1e-12);
+        assert!(norm(&v, Some(NormOrder::Fro), None, false).is_err());
+    }
+
+    #[test]
+    fn l2_does_not_overflow_or_underflow() {
+        let big = array(&[3e200, 4e200], &[2]);
+        assert_close(&norm(&big, None, None, false).unwrap(), &[5e200], 1e-15);
+        let small = array(&[3e-200, 4e-200], &[2]);
+        assert_close(&norm(&small, None, None, false).unwrap(), &[5e-200], 1e-15);
+        let p = norm(&big, Some(NormOrder::P(3.0)), None, false).unwrap();
+        assert!(p.item().is_finite());
+    }
+
+    #[test]
+    fn vector_norms_along_axis() {
+        let a = array(&[3.0, 4.0, 0.0, 1.0, 0.0, 0.0], &[2, 3]);
+        assert_close(
+            &norm(&a, None, Some(&[1]), false).unwrap(),
+            &[5.0, 1.0],
+            1e-15,
+        );
+        let columns = norm(&a, Some(NormOrder::P(1.0)), Some(&[0]), true).unwrap();
+        assert_eq!(columns.shape(), &[1, 3]);
+        assert_close(&columns, &[4.0, 4.0, 0.0], 1e-15);
+    }
+
+    #[test]
+    fn matrix_norms() {
+        let a = array(&[1.0, -2.0, 3.0, 4.0], &[2, 2]);
+        let norm_of = |ord| norm(&a, Some(ord), None, false).unwrap().to_vec()[0];
+        assert!((norm(&a, None, None, false).unwrap().item() - 30f64.sqrt()).abs() < 1e-12);
+        assert!((norm_of(NormOrder::Fro) - 30f64.sqrt()).abs() < 1e-12);
+        assert_eq!(norm_of(NormOrder::P(1.0)), 6.0);
+        assert_eq!(norm_of(NormOrder::P(-1.0)), 4.0);
+        assert_eq!(norm_of(NormOrder::Inf), 7.0);
+        assert_eq!(norm_of(NormOrder::NegInf), 3.0);
+
+        // The singular values s1, s2 satisfy s1^2 + s2^2 = 30 and
+        // s1 * s2 = |det| = 10.
+        let (s1, s2) = ((30.0 + 20.0f64).sqrt() / 2.0, (30.0 - 20.0f64).sqrt() / 2.0);
+        let (s1, s2) = (s1 + s2, s1 - s2);
+        assert!((norm_of(NormOrder::P(2.0)) - s1).abs() < 1e-12);
+        assert!((norm_of(NormOrder::P(-2.0)) - s2).abs() < 1e-12);
+        assert!((norm_of(NormOrder::Nuc) - (s1 + s2)).abs() < 1e-12);
+        assert!(norm(&a, Some(NormOrder::P(3.0)), None, false).is_err());
+    }
+
+    #[test]
+    fn matrix_norms_over_axes() {
+        let a = array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], &[2, 2, 2]);
+        let result = norm(&a, Some(NormOrder::P(1.0)), Some(&[0, 2]), false).unwrap();
+        assert_close(&result, &[8.0, 12.0], 1e-15);
+        let result = norm(&a, Some(NormOrder::NegInf), Some(&[2, 0]), true).unwrap();
+        assert_eq!(result.shape(), &[1, 2, 1]);
+        assert_close(&result, &[6.0, 10.0], 1e-15);
+        assert!(norm(&a, Some(NormOrder::Fro), None, false).is_err());
+        assert!(norm(&a, None, Some(&[1, -2]), false).is_err());
+        assert!(norm(&a, None, Some(&[0, 1, 2]), false).is_err());
+    }
+}
@@ -0,0 +1,382 @@
+use num_traits::Float;
+
+use super::{norm2, split_matrix_shape, to_array, Matrices};
+use crate::array::Array;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView};
+
+/// Shape of the factors returned by [`qr`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QrMode {
+    /// `Q` is `m x k` and `R` is `k x n`, with `k = min(m, n)`.
+    Reduced,
+    /// `Q` is `m x m` and `R` is `m x n`.
+    Complete,
+}
+
+/// Householder QR factorisation of an `m x n` row-major matrix, optionally
+/// with column pivoting, such that `A P = Q R`.
+///
+/// `Q` is kept implicitly as the product of the reflectors `I - beta v v^T`,
+/// where each `v` only covers rows `j..m`.
+pub(crate) struct Householder<T> {
+    pub m: usize,
+    pub n: usize,
+    pub r: Vec<T>,
+    pub perm: Vec<usize>,
+    reflectors: Vec<(Vec<T>, T)>,
+}
+
+impl<T: Float> Householder<T> {
+    pub fn new(a: &[T], m: usize, n: usize, pivoting: bool) -> Self {
+        let mut r = a.to_vec();
+        let mut perm = (0..n).collect::<Vec<usize>>();
+        let mut reflectors = Vec::with_capacity(m.min(n));
+
+        for j in 0..m.min(n) {
+            if pivoting {
+                let column_norm = |col: usize| norm2((j..m).map(|i| r[i * n + col]));
+                let pivot = (j..n)
+                    .map(|col| (col, column_norm(col)))
+                    .fold((j, T::neg_infinity()), |best, cand| {
+                        if cand.1 > best.1 {
+                            cand
+                        } else {
+                            best
+                        }
+                    })
+                    .0;
+                if pivot != j {
+                    for i in 0..m {
+                        r.swap(i * n + j, i * n + pivot);
+                    }
+                    perm.swap(j, pivot);
+                }
+            }
+
+            let mut v = (j..m).map(|i| r[i * n + j]).collect::<Vec<T>>();
+            let norm = norm2(v.iter().copied());
+            if norm == T::zero() {
+                reflectors.push((v, T::zero()));
+                continue;
+            }
+            let alpha = if v[0] > T::zero() { -norm } else { norm };
+            v[0] = v[0] - alpha;
+            let beta = T::one() / (norm * v[0].abs());
+            let reflector = (v, beta);
+
+            apply_reflector(&reflector, &mut r, n, j, j..n);
+            r[j * n + j] = alpha;
+            for i in j + 1..m {
+                r[i * n + j] = T::zero();
+            }
+            reflectors.push(reflector);
+        }
+
+        Self {
+            m,
+            n,
+            r,
+            perm,
+            reflectors,
+        }
+    }
+
+    /// Rows `0..rows` of `R`.
+    pub fn r(&self, rows: usize) -> Vec<T> {
+        let mut r = self.r[..rows.min(self.m) * self.n].to_vec();
+        r.resize(rows * self.n, T::zero());
+        r
+    }
+
+    /// The first `cols` columns of `Q`, as an `m x cols` matrix.
+    pub fn q(&self, cols: usize) -> Vec<T> {
+        let mut q = vec![T::zero(); self.m * cols];
+        for i in 0..cols.min(self.m) {
+            q[i * cols + i] = T::one();
+        }
+        for (j, reflector) in self.reflectors.iter().enumerate().rev() {
+            apply_reflector(reflector, &mut q, cols, j, 0..cols);
+        }
+        q
+    }
+
+    /// Overwrites the `m x k` matrix `b` with `Q^T b`.
+    pub fn apply_qt(&self, b: &mut [T], k: usize) {
+        for (j, reflector) in self.reflectors.iter().enumerate() {
+            apply_reflector(reflector, b, k, j, 0..k);
+        }
+    }
+}
+
+/// Applies `I - beta v v^T` to rows `first_row..` and columns `cols` of a
+/// row-major matrix with `ncols` columns.
+fn apply_reflector<T: Float>(
+    (v, beta): &(Vec<T>, T),
+    a: &mut [T],
+    ncols: usize,
+    first_row: usize,
+    cols: std::ops::Range<usize>,
+) {
+    if *beta == T::zero() {
+        return;
+    }
+    for col in cols {
+        let dot = v.iter().enumerate().fold(T::zero(), |acc, (i, &vi)| {
+            acc + vi * a[(first_row + i) * ncols + col]
+        });
+        let factor = *beta * dot;
+        for (i, &vi) in v.iter().enumerate() {
+            let idx = (first_row + i) * ncols + col;
+            a[idx] = a[idx] - factor * vi;
+        }
+    }
+}
+
+type Qr<StorageType> = (
+    Array<StorageType, ContiguousView>,
+    Array<StorageType, ContiguousView>,
+);
+
+/// Householder QR decomposition `A = Q R` of each matrix in the batch.
+pub fn qr<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+    mode: QrMode,
+) -> Result<Qr<StorageType>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_array(a)?;
+    let (m, n) = (a.rows, a.cols);
+    let q_cols = match mode {
+        QrMode::Reduced => m.min(n),
+        QrMode::Complete => m,
+    };
+
+    let mut q = Vec::with_capacity(a.len() * m * q_cols);
+    let mut r = Vec::with_capacity(a.len() * q_cols * n);
+    for matrix in a.iter() {
+        let factor = Householder::new(matrix, m, n, false);
+        q.extend(factor.q(q_cols));
+        r.extend(factor.r(q_cols));
+    }
+
+    Ok((
+        to_array(&a.batch, &[m, q_cols], q),
+        to_array(&a.batch, &[q_cols, n], r),
+    ))
+}
+
+type Lstsq<StorageType> = (
+    Array<StorageType, ContiguousView>,
+    Array<StorageType, ContiguousView>,
+    usize,
+);
+
+/// Least-squares solution of `A X = B` with minimum norm.
+///
+/// `a` is an `m x n` matrix and `b` has `m` rows, either as a vector or as
+/// an `m x k` matrix. Returns the solution, the sums of squared residuals of
+/// each column of `b`, and the effective rank of `a`. As in NumPy, the
+/// residuals are only reported when `a` has full column rank and `m > n`,
+/// and are empty otherwise.
+///
+/// The solution is computed from a column-pivoted QR factorisation followed
+/// by a complete orthogonal decomposition of the leading rows of `R`, which
+/// handles over-determined, under-determined and rank-deficient systems
+/// without forming the normal equations.
+pub fn lstsq<T, StorageType, LhsView, RhsView>(
+    a: &Array<StorageType, LhsView>,
+    b: &Array<StorageType, RhsView>,
+) -> Result<Lstsq<StorageType>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    LhsView: ArrayView,
+    RhsView: ArrayView,
+{
+    let (batch, m, n) = split_matrix_shape(a.shape())?;
+    if !batch.is_empty() {
+        return Err(format!(
+            "lstsq: expected a 2-D matrix, got shape {:?}",
+            a.shape()
+        ));
+    }
+    let vector = b.ndim() == 1;
+    let k = match b.shape() {
+        [rows] if *rows == m => 1,
+        [rows, cols] if *rows == m => *cols,
+        _ => {
+            return Err(format!(
+                "lstsq: shapes {:?} and {:?} are not compatible",
+                a.shape(),
+                b.shape()
+            ))
+        }
+    };
+
+    let factor = Householder::new(&a.to_vec(), m, n, true);
+    let max_diag = (0..m.min(n))
+        .map(|i| factor.r[i * n + i].abs())
+        .fold(T::zero(), T::max);
+    let rcond = T::epsilon() * T::from(m.max(n)).unwrap();
+    let rank = (0..m.min(n))
+        .take_while(|&i| factor.r[i * n + i].abs() > rcond * max_diag)
+        .count();
+
+    let mut c = b.to_vec();
+    factor.apply_qt(&mut c, k);
+
+    // [R11 R12] = R2^T Q2^T, so the minimum norm solution of
+    // [R11 R12] y = c1 is y = Q2 R2^-T c1.
+    let mut r_top_t = vec![T::zero(); n * rank];
+    for i in 0..rank {
+        for j in 0..n {
+            r_top_t[j * rank + i] = factor.r[i * n + j];
+        }
+    }
+    let second = Householder::new(&r_top_t, n, rank, false);
+    let mut z = c[..rank * k].to_vec();
+    for i in 0..rank {
+        for p in 0..i {
+            let factor = second.r[p * rank + i];
+            for j in 0..k {
+                z[i * k + j] = z[i * k + j] - factor * z[p * k + j];
+            }
+        }
+        let diag = second.r[i * rank + i];
+        for j in 0..k {
+            z[i * k + j] = z[i * k + j] / diag;
+        }
+    }
+    let q2 = second.q(rank);
+    let mut x = vec![T::zero(); n * k];
+    for (row, &col) in factor.perm.iter().enumerate() {
+        for p in 0..rank {
+            for j in 0..k {
+                x[col * k + j] = x[col * k + j] + q2[row * rank + p] * z[p * k + j];
+            }
+        }
+    }
+
+    let residuals = if rank == n && m > n {
+        (0..k)
+            .map(|j| {
+                let norm = norm2((n..m).map(|i| c[i * k + j]));
+                norm * norm
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let x = if vector {
+        to_array(&[], &[n], x)
+    } else {
+        to_array(&[], &[n, k], x)
+    };
+    let residuals_len = residuals.len();
+    Ok((x, to_array(&[], &[residuals_len], residuals), rank))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{array, assert_close};
+
+    fn assert_orthonormal_columns<S, V>(q: &Array<S, V>)
+    where
+        S: Storage<Stored = f64>,
+        V: ArrayView,
+    {
+        let k = q.shape()[1];
+        let mut identity = vec![0.0; k * k];
+        for i in 0..k {
+            identity[i * k + i] = 1.0;
+        }
+        assert_close(&q.t().matmul(q).unwrap(), &identity, 1e-12);
+    }
+
+    #[test]
+    fn qr_reduced_tall() {
+        let a = array(
+            &[
+                12.0, -51.0, 4.0, 6.0, 167.0, -68.0, -4.0, 24.0, -41.0, 1.0, 2.0, 3.0,
+            ],
+            &[4, 3],
+        );
+        let (q, r) = qr(&a, QrMode::Reduced).unwrap();
+        assert_eq!(q.shape(), &[4, 3]);
+        assert_eq!(r.shape(), &[3, 3]);
+        assert_orthonormal_columns(&q);
+        assert_close(&r.slice(crate::s![1.., 0]), &[0.0, 0.0], 0.0);
+        assert_close(&q.matmul(&r).unwrap(), &a.to_vec(), 1e-12);
+    }
+
+    #[test]
+    fn qr_complete_and_wide() {
+        let a = array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+        let (q, r) = qr(&a.t(), QrMode::Complete).unwrap();
+        assert_eq!(q.shape(), &[3, 3]);
+        assert_eq!(r.shape(), &[3, 2]);
+        assert_orthonormal_columns(&q);
+        assert_close(&q.matmul(&r).unwrap(), &a.t().to_vec(), 1e-12);
+
+        let (q, r) = qr(&a, QrMode::Reduced).unwrap();
+        assert_eq!(q.shape(), &[2, 2]);
+        assert_eq!(r.shape(), &[2, 3]);
+        assert_close(&q.matmul(&r).unwrap(), &a.to_vec(), 1e-12);
+    }
+
+    #[test]
+    fn qr_batched() {
+        let a = array(&[2.0, 1.0, 1.0, 3.0, 0.0, 1.0, 1.0, 0.0], &[2, 2, 2]);
+        let (q, r) = qr(&a, QrMode::Reduced).unwrap();
+        assert_eq!(q.shape(), &[2, 2, 2]);
+        assert_close(&q.matmul(&r).unwrap(), &a.to_vec(), 1e-12);
+    }
+
+    #[test]
+    fn lstsq_overdetermined() {
+        // Fit y = 1 + 2 x to noisy points.
+        let a = array(&[1.0, 0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0], &[4, 2]);
+        let b = array(&[1.1, 2.9, 5.2, 6.8], &[4]);
+        let (x, residuals, rank) = lstsq(&a, &b).unwrap();
+        assert_eq!(rank, 2);
+        assert_close(&x, &[1.09, 1.94], 1e-12);
+        let fitted = a.matmul(&x).unwrap();
+        let error = (&fitted - &b).to_vec().iter().map(|e| e * e).sum::<f64>();
+        assert_close(&residuals, &[error], 1e-12);
+    }
+
+    #[test]
+    fn lstsq_underdetermined_minimum_norm() {
+        let a = array(&[1.0, 1.0, 1.0, 1.0, 2.0, 3.0], &[2, 3]);
+        let b = array(&[6.0, 14.0], &[2, 1]);
+        let (x, residuals, rank) = lstsq(&a, &b).unwrap();
+        assert_eq!(rank, 2);
+        assert_eq!(x.shape(), &[3, 1]);
+        assert_eq!(residuals.shape(), &[0]);
+        // The minimum norm solution is orthogonal to the null space (1, -2, 1).
+        assert_close(&x, &[1.0, 2.0, 3.0], 1e-12);
+    }
+
+    #[test]
+    fn lstsq_rank_deficient() {
+        let a = array(&[1.0, 2.0, 2.0, 4.0, 3.0, 6.0], &[3, 2]);
+        let b = array(&[1.0, 2.0, 3.0], &[3]);
+        let (x, residuals, rank) = lstsq(&a, &b).unwrap();
+        assert_eq!(rank, 1);
+        assert_eq!(residuals.shape(), &[0]);
+        assert_close(&x, &[0.2, 0.4], 1e-12);
+    }
+
+    #[test]
+    fn lstsq_errors() {
+        let a = array(&[1.0, 2.0, 3.0, 4.0], &[2, 2]);
+        assert!(lstsq(&a, &array(&[1.0, 2.0, 3.0], &[3])).is_err());
+        assert!(lstsq(&a.reshape([1usize, 2, 2]), &array(&[1.0, 2.0], &[2])).is_err());
+    }
+}
@@ -0,0 +1,413 @@
+use num_traits::Float;
+
+use super::{norm2, to_array, Matrices};
+use crate::array::Array;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView};
+
+/// Upper bound on the number of Jacobi sweeps. Convergence is quadratic, so
+/// this is only reached for matrices containing NaN or infinities.
+const MAX_SWEEPS: usize = 64;
+
+/// Singular value decomposition of an `m x n` row-major matrix.
+///
+/// `u` is `m x ucols` and `vt` is `vrows x n`, both row-major, with
+/// `ucols = vrows = min(m, n)` unless full matrices were requested. Singular
+/// values are sorted in descending order.
+pub(crate) struct Svd<T> {
+    pub u: Vec<T>,
+    pub s: Vec<T>,
+    pub vt: Vec<T>,
+}
+
+impl<T: Float> Svd<T> {
+    pub fn new(a: &[T], m: usize, n: usize, full_matrices: bool) -> Self {
+        if m >= n {
+            let (u, s, v) = jacobi_svd(a, m, n, full_matrices);
+            Self {
+                u: columns_to_rows(&u, m),
+                s,
+                vt: transpose(&columns_to_rows(&v, n), n, v.len()),
+            }
+        } else {
+            // A^T = U' S V'^T, so A = V' S U'^T.
+            let at = transpose(a, m, n);
+            let (u, s, v) = jacobi_svd(&at, n, m, full_matrices);
+            Self {
+                u: columns_to_rows(&v, m),
+                s,
+                vt: transpose(&columns_to_rows(&u, n), n, u.len()),
+            }
+        }
+    }
+}
+
+/// Transposes a row-major `rows x cols` matrix.
+fn transpose<T: Float>(data: &[T], rows: usize, cols: usize) -> Vec<T> {
+    let mut result = vec![T::zero(); data.len()];
+    for i in 0..rows {
+        for j in 0..cols {
+            result[j * rows + i] = data[i * cols + j];
+        }
+    }
+    result
+}
+
+/// Lays out columns of length `rows` as a row-major matrix.
+fn columns_to_rows<T: Float>(columns: &[Vec<T>], rows: usize) -> Vec<T> {
+    let cols = columns.len();
+    let mut result = vec![T::zero(); rows * cols];
+    for (j, column) in columns.iter().enumerate() {
+        for (i, &value) in column.iter().enumerate() {
+            result[i * cols + j] = value;
+        }
+    }
+    result
+}
+
+/// One-sided Jacobi SVD of an `m x n` row-major matrix with `m >= n`.
+///
+/// Plane rotations are applied to pairs of columns of `A` until all columns
+/// are mutually orthogonal, at which point their norms are the singular
+/// values and the accumulated rotations form `V`. Returns the columns of `U`
+/// and `V` along with the singular values.
+fn jacobi_svd<T: Float>(
+    a: &[T],
+    m: usize,
+    n: usize,
+    full_matrices: bool,
+) -> (Vec<Vec<T>>, Vec<T>, Vec<Vec<T>>) {
+    let mut u = (0..n)
+        .map(|j| (0..m).map(|i| a[i * n + j]).collect::<Vec<T>>())
+        .collect::<Vec<Vec<T>>>();
+    let mut v = (0..n)
+        .map(|j| {
+            (0..n)
+                .map(|i| if i == j { T::one() } else { T::zero() })
+                .collect()
+        })
+        .collect::<Vec<Vec<T>>>();
+
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let alpha = dot(&u[p], &u[p]);
+                let beta = dot(&u[q], &u[q]);
+                let gamma = dot(&u[p], &u[q]);
+                if gamma == T::zero() || gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                let zeta = (beta - alpha) / (gamma + gamma);
+                let t = zeta.signum() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+                let c = T::one() / (T::one() + t * t).sqrt();
+                let s = c * t;
+                rotate(&mut u, p, q, c, s);
+                rotate(&mut v, p, q, c, s);
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let mut s = u
+        .iter()
+        .map(|column| norm2(column.iter().copied()))
+        .collect::<Vec<T>>();
+    let mut order = (0..n).collect::<Vec<usize>>();
+    order.sort_by(|&i, &j| s[j].partial_cmp(&s[i]).unwrap_or(std::cmp::Ordering::Equal));
+    let mut u = order.iter().map(|&j| u[j].clone()).collect::<Vec<Vec<T>>>();
+    let v = order.iter().map(|&j| v[j].clone()).collect::<Vec<Vec<T>>>();
+    s = order.iter().map(|&j| s[j]).collect();
+
+    // Columns of U belonging to zero singular values carry no information,
+    // so they are rebuilt together with any extra columns for full matrices.
+    let largest = s.first().copied().unwrap_or_else(T::zero);
+    let mut valid = 0;
+    for (column, &sigma) in u.iter_mut().zip(s.iter()) {
+        if sigma > T::zero() && sigma > largest * T::epsilon() * T::from(m).unwrap() {
+            column.iter_mut().for_each(|x| *x = *x / sigma);
+            valid += 1;
+        } else {
+            break;
+        }
+    }
+    u.truncate(valid);
+    complete_basis(&mut u, m, if full_matrices { m } else { n });
+
+    (u, s, v)
+}
+
+fn dot<T: Float>(x: &[T], y: &[T]) -> T {
+    x.iter()
+        .zip(y.iter())
+        .fold(T::zero(), |acc, (&a, &b)| acc + a * b)
+}
+
+fn rotate<T: Float>(columns: &mut [Vec<T>], p: usize, q: usize, c: T, s: T) {
+    let (head, tail) = columns.split_at_mut(q);
+    for (x, y) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+        let (xp, xq) = (*x, *y);
+        *x = c * xp - s * xq;
+        *y = s * xp + c * xq;
+    }
+}
+
+/// Extends orthonormal columns of length `rows` to `target` columns using
+/// Gram-Schmidt on the standard basis vectors.
+fn complete_basis<T: Float>(columns: &mut Vec<Vec<T>>, rows: usize, target: usize) {
+    let half = T::from(0.5).unwrap();
+    for e in 0..rows {
+        if columns.len() >= target {
+            break;
+        }
+        let mut candidate = vec![T::zero(); rows];
+        candidate[e] = T::one();
+        // Orthogonalising twice keeps the result orthogonal to working
+        // precision.
+        for _ in 0..2 {
+            for column in columns.iter() {
+                let projection = dot(column, &candidate);
+                for (x, &c) in candidate.iter_mut().zip(column.iter()) {
+                    *x = *x - projection * c;
+                }
+            }
+        }
+        let norm = norm2(candidate.iter().copied());
+        if norm > half {
+            candidate.iter_mut().for_each(|x| *x = *x / norm);
+            columns.push(candidate);
+        }
+    }
+}
+
+type SvdArrays<StorageType> = (
+    Array<StorageType, ContiguousView>,
+    Array<StorageType, ContiguousView>,
+    Array<StorageType, ContiguousView>,
+);
+
+/// Singular value decomposition `A = U diag(S) Vt` of each matrix in the
+/// batch, computed with one-sided Jacobi rotations.
+///
+/// With `full_matrices`, `U` is `m x m` and `Vt` is `n x n`; otherwise they
+/// are `m x k` and `k x n` with `k = min(m, n)`. The singular values are
+/// returned in descending order.
+pub fn svd<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+    full_matrices: bool,
+) -> Result<SvdArrays<StorageType>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_array(a)?;
+    let (m, n) = (a.rows, a.cols);
+    let k = m.min(n);
+    let (ucols, vrows) = if full_matrices { (m, n) } else { (k, k) };
+
+    let mut u = Vec::with_capacity(a.len() * m * ucols);
+    let mut s = Vec::with_capacity(a.len() * k);
+    let mut vt = Vec::with_capacity(a.len() * vrows * n);
+    for matrix in a.iter() {
+        let decomposition = Svd::new(matrix, m, n, full_matrices);
+        u.extend(decomposition.u);
+        s.extend(decomposition.s);
+        vt.extend(decomposition.vt);
+    }
+
+    Ok((
+        to_array(&a.batch, &[m, ucols], u),
+        to_array(&a.batch, &[k], s),
+        to_array(&a.batch, &[vrows, n], vt),
+    ))
+}
+
+/// Singular values of every matrix in the batch, in descending order.
+fn singular_values<T: Float>(a: &Matrices<T>) -> impl Iterator<Item = Vec<T>> + '_ {
+    a.iter()
+        .map(move |matrix| Svd::new(matrix, a.rows, a.cols, false).s)
+}
+
+/// Default cutoff below which singular values are treated as zero.
+fn default_tolerance<T: Float>(s: &[T], m: usize, n: usize) -> T {
+    let largest = s.first().copied().unwrap_or_else(T::zero);
+    largest * T::from(m.max(n)).unwrap() * T::epsilon()
+}
+
+/// Moore-Penrose pseudo-inverse of each matrix in the batch. Singular values
+/// at or below `rcond` times the largest singular value are treated as zero;
+/// by default `rcond` is `max(m, n)` times the machine epsilon.
+pub fn pinv<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+    rcond: Option<T>,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_array(a)?;
+    let (m, n) = (a.rows, a.cols);
+    let k = m.min(n);
+
+    let mut data = Vec::with_capacity(a.data.len());
+    for matrix in a.iter() {
+        let Svd { u, s, vt } = Svd::new(matrix, m, n, false);
+        let largest = s.first().copied().unwrap_or_else(T::zero);
+        let cutoff = rcond.map_or_else(|| default_tolerance(&s, m, n), |rcond| rcond * largest);
+        // pinv(A) = V diag(1 / S) U^T, an n x m matrix.
+        let mut result = vec![T::zero(); n * m];
+        for (p, &sigma) in s.iter().enumerate() {
+            if sigma <= cutoff {
+                continue;
+            }
+            for i in 0..n {
+                let factor = vt[p * n + i] / sigma;
+                for j in 0..m {
+                    result[i * m + j] = result[i * m + j] + factor * u[j * k + p];
+                }
+            }
+        }
+        data.extend(result);
+    }
+    Ok(to_array(&a.batch, &[n, m], data))
+}
+
+/// Rank of each matrix in the batch, counting the singular values above
+/// `tol`. By default `tol` is the largest singular value times `max(m, n)`
+/// times the machine epsilon.
+pub fn matrix_rank<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+    tol: Option<T>,
+) -> Result<Array<StorageType::Rebind<usize>, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_array(a)?;
+    let ranks = singular_values(&a)
+        .map(|s| {
+            let tol = tol.unwrap_or_else(|| default_tolerance(&s, a.rows, a.cols));
+            s.iter().filter(|&&sigma| sigma > tol).count()
+        })
+        .collect::<Vec<usize>>();
+    Ok(Array::from_parts(
+        StorageType::Rebind::<usize>::from(ranks),
+        ContiguousView::new(&a.batch),
+    ))
+}
+
+/// Condition number in the 2-norm of each matrix in the batch, the ratio of
+/// the largest to the smallest singular value. Singular matrices have an
+/// infinite condition number.
+pub fn cond<T, StorageType, ViewType>(
+    a: &Array<StorageType, ViewType>,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Float,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let a = Matrices::from_array(a)?;
+    let data = singular_values(&a)
+        .map(|s| match (s.first(), s.last()) {
+            (Some(&largest), Some(&smallest)) => largest / smallest,
+            _ => T::zero(),
+        })
+        .collect();
+    Ok(to_array(&a.batch, &[], data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{array, assert_close, TestArray};
+
+    fn diag(s: &TestArray, rows: usize, cols: usize) -> TestArray {
+        let values = s.to_vec();
+        let mut data = vec![0.0; rows * cols];
+        for (i, &value) in values.iter().enumerate() {
+            data[i * cols + i] = value;
+        }
+        array(&data, &[rows, cols])
+    }
+
+    fn identity(n: usize) -> Vec<f64> {
+        let mut data = vec![0.0; n * n];
+        for i in 0..n {
+            data[i * n + i] = 1.0;
+        }
+        data
+    }
+
+    #[test]
+    fn svd_reduced_reconstructs() {
+        let a = array(&[3.0, 2.0, 2.0, 2.0, 3.0, -2.0], &[2, 3]);
+        let (u, s, vt) = svd(&a, false).unwrap();
+        assert_eq!(
+            (u.shape(), s.shape(), vt.shape()),
+            (&[2, 2][..], &[2][..], &[2, 3][..])
+        );
+        assert_close(&s, &[5.0, 3.0], 1e-12);
+        let reconstructed = u.matmul(&diag(&s, 2, 2)).unwrap().matmul(&vt).unwrap();
+        assert_close(&reconstructed, &a.to_vec(), 1e-12);
+        assert_close(&vt.matmul(&vt.t()).unwrap(), &identity(2), 1e-12);
+    }
+
+    #[test]
+    fn svd_full_matrices_tall() {
+        let a = array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], &[4, 2]);
+        let (u, s, vt) = svd(&a, true).unwrap();
+        assert_eq!(u.shape(), &[4, 4]);
+        assert_eq!(vt.shape(), &[2, 2]);
+        assert_close(&u.t().matmul(&u).unwrap(), &identity(4), 1e-12);
+        let reconstructed = u.matmul(&diag(&s, 4, 2)).unwrap().matmul(&vt).unwrap();
+        assert_close(&reconstructed, &a.to_vec(), 1e-12);
+    }
+
+    #[test]
+    fn svd_rank_deficient_batched() {
+        let a = array(&[1.0, 2.0, 2.0, 4.0, 0.0, 0.0, 0.0, 0.0], &[2, 2, 2]);
+        let (u, s, vt) = svd(&a, false).unwrap();
+        assert_close(&s, &[5.0, 0.0, 0.0, 0.0], 1e-12);
+        for b in 0..2 {
+            let u = u.slice(crate::s![b]);
+            assert_close(&u.t().matmul(&u).unwrap(), &identity(2), 1e-12);
+            let vt = vt.slice(crate::s![b]);
+            assert_close(&vt.matmul(&vt.t()).unwrap(), &identity(2), 1e-12);
+        }
+    }
+
+    #[test]
+    fn pinv_full_rank_and_deficient() {
+        let a = array(&[4.0, 7.0, 2.0, 6.0], &[2, 2]);
+        assert_close(&pinv(&a, None).unwrap(), &[0.6, -0.7, -0.2, 0.4], 1e-12);
+
+        let a = array(&[1.0, 2.0, 2.0, 4.0, 3.0, 6.0], &[3, 2]);
+        let a_pinv = pinv(&a, None).unwrap();
+        assert_eq!(a_pinv.shape(), &[2, 3]);
+        let aa = a.matmul(&a_pinv).unwrap().matmul(&a).unwrap();
+        assert_close(&aa, &a.to_vec(), 1e-12);
+        assert_close(
+            &a_pinv,
+            &[1.0, 2.0, 3.0, 2.0, 4.0, 6.0].map(|x| x / 70.0),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn rank_and_cond() {
+        let a = array(&[1.0, 2.0, 2.0, 4.0, 3.0, 0.0, 0.0, 4.0], &[2, 2, 2]);
+        assert_eq!(matrix_rank(&a, None).unwrap().to_vec(), vec![1, 2]);
+        assert_eq!(matrix_rank(&a, Some(4.5)).unwrap().to_vec(), vec![1, 0]);
+        let c = cond(&a).unwrap();
+        assert!(c.to_vec()[0] > 1e15);
+        assert_close(&c.slice(crate::s![1..]), &[4.0 / 3.0], 1e-12);
+    }
+}
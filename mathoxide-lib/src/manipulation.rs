@@ -0,0 +1,452 @@
+use num_traits::Num;
+
+use crate::array::{Array, AsStrided};
+use crate::shape_utils::normalize_axis;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView, StridedView};
+
+/// How [`split`] and [`array_split`] divide an axis.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sections {
+    /// Into this many pieces of (nearly) equal length.
+    Count(usize),
+    /// At these indices along the axis. Indices past the end of the axis
+    /// are clipped, and a decreasing index gives an empty piece.
+    Indices(Vec<usize>),
+}
+
+impl From<usize> for Sections {
+    fn from(n: usize) -> Self {
+        Sections::Count(n)
+    }
+}
+
+impl From<Vec<usize>> for Sections {
+    fn from(indices: Vec<usize>) -> Self {
+        Sections::Indices(indices)
+    }
+}
+
+impl From<&[usize]> for Sections {
+    fn from(indices: &[usize]) -> Self {
+        Sections::Indices(indices.to_vec())
+    }
+}
+
+impl<const N: usize> From<[usize; N]> for Sections {
+    fn from(indices: [usize; N]) -> Self {
+        Sections::Indices(indices.to_vec())
+    }
+}
+
+/// A view of `array` with a new axis of length 1 at each of `axes`, which
+/// index the result and must be in increasing order.
+fn with_unit_axes<T, StorageType, ViewType>(
+    array: &Array<StorageType, ViewType>,
+    axes: &[usize],
+) -> Array<StorageType, StridedView>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let mut shape = array.shape().to_vec();
+    let mut stride = array.stride().to_vec();
+    for &axis in axes {
+        shape.insert(axis, 1);
+        stride.insert(axis, 0);
+    }
+    Array::from_parts(
+        array.storage().clone(),
+        StridedView::new_with_offset(shape, stride, array.storage_offset()),
+    )
+}
+
+/// A view of the elements `start..end` along `axis` of `array`.
+fn narrow<T, StorageType, ViewType>(
+    array: &Array<StorageType, ViewType>,
+    axis: usize,
+    start: usize,
+    end: usize,
+) -> Array<StorageType, StridedView>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    let mut shape = array.shape().to_vec();
+    shape[axis] = end - start;
+    let offset = array
+        .storage_offset()
+        .wrapping_add(start.wrapping_mul(array.stride()[axis]));
+    Array::from_parts(
+        array.storage().clone(),
+        StridedView::new_with_offset(shape, array.stride(), offset),
+    )
+}
+
+/// Puts each of `arrays` behind a [`StridedView`] without copying.
+fn strided<StorageType: Storage>(
+    arrays: &[&dyn AsStrided<StorageType>],
+) -> Vec<Array<StorageType, StridedView>> {
+    arrays.iter().map(|array| array.as_strided()).collect()
+}
+
+/// Joins `arrays` along an existing `axis`. All arrays must have the same
+/// number of dimensions and the same shape except along `axis`. The arrays
+/// may mix view types, such as a contiguous array and a transpose.
+pub fn concatenate<T, StorageType>(
+    arrays: &[&dyn AsStrided<StorageType>],
+    axis: isize,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    join(&strided(arrays), axis)
+}
+
+/// [`concatenate`] once the arrays share a view type.
+fn join<T, StorageType>(
+    arrays: &[Array<StorageType, StridedView>],
+    axis: isize,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    let first = arrays
+        .first()
+        .ok_or_else(|| "concatenate: need at least one array".to_string())?;
+    if first.ndim() == 0 {
+        return Err("concatenate: 0-D arrays cannot be concatenated".to_string());
+    }
+    let axis = normalize_axis(axis, first.ndim())?;
+
+    let mut shape = first.shape().to_vec();
+    shape[axis] = 0;
+    for (idx, array) in arrays.iter().enumerate() {
+        if array.ndim() != first.ndim() {
+            return Err(format!(
+                "concatenate: array {} with shape {:?} has {} dimensions, but array 0 with \
+                 shape {:?} has {}",
+                idx,
+                array.shape(),
+                array.ndim(),
+                first.shape(),
+                first.ndim()
+            ));
+        }
+        let mismatch =
+            (0..first.ndim()).find(|&dim| dim != axis && array.shape()[dim] != first.shape()[dim]);
+        if let Some(dim) = mismatch {
+            return Err(format!(
+                "concatenate: along axis {}, array {} with shape {:?} does not match array 0 \
+                 with shape {:?} at axis {}",
+                axis,
+                idx,
+                array.shape(),
+                first.shape(),
+                dim
+            ));
+        }
+        shape[axis] += array.shape()[axis];
+    }
+
+    let result = Array::<StorageType, ContiguousView>::zeros(&shape);
+    let mut start = 0;
+    for array in arrays {
+        let end = start + array.shape()[axis];
+        narrow(&result, axis, start, end).assign(array);
+        start = end;
+    }
+    Ok(result)
+}
+
+/// Joins `arrays` along a new `axis`, which indexes the result. All arrays
+/// must have the same shape.
+pub fn stack<T, StorageType>(
+    arrays: &[&dyn AsStrided<StorageType>],
+    axis: isize,
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    let arrays = strided(arrays);
+    let first = arrays
+        .first()
+        .ok_or_else(|| "stack: need at least one array".to_string())?;
+    let axis = normalize_axis(axis, first.ndim() + 1)?;
+    for (idx, array) in arrays.iter().enumerate() {
+        if array.shape() != first.shape() {
+            return Err(format!(
+                "stack: array {} with shape {:?} does not match array 0 with shape {:?}; \
+                 all arrays must have the same shape to stack along axis {}",
+                idx,
+                array.shape(),
+                first.shape(),
+                axis
+            ));
+        }
+    }
+    let expanded = arrays
+        .iter()
+        .map(|array| with_unit_axes(array, &[axis]))
+        .collect::<Vec<_>>();
+    join(&expanded, axis as isize)
+}
+
+/// Concatenates `arrays` along `axis` after adding the unit axes
+/// `promoted[ndim]` to each array with `ndim` dimensions.
+fn stack_promoted<T, StorageType>(
+    arrays: &[&dyn AsStrided<StorageType>],
+    axis: usize,
+    promoted: &[&[usize]],
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    let promoted = strided(arrays)
+        .iter()
+        .map(|array| {
+            with_unit_axes(
+                array,
+                promoted.get(array.ndim()).copied().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    join(&promoted, axis as isize)
+}
+
+/// Joins `arrays` column-wise: along the first axis for 1-D arrays and
+/// along the second axis otherwise. 0-D arrays are treated as 1-D.
+pub fn hstack<T, StorageType>(
+    arrays: &[&dyn AsStrided<StorageType>],
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    let axis = match arrays.first() {
+        Some(first) if first.as_strided().ndim() > 1 => 1,
+        _ => 0,
+    };
+    stack_promoted(arrays, axis, &[&[0]])
+}
+
+/// Joins `arrays` row-wise, along the first axis. Arrays of shape `[n]`
+/// are treated as `[1, n]`.
+pub fn vstack<T, StorageType>(
+    arrays: &[&dyn AsStrided<StorageType>],
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    stack_promoted(arrays, 0, &[&[0, 1], &[0]])
+}
+
+/// Joins `arrays` depth-wise, along the third axis. Arrays of shape `[n]`
+/// are treated as `[1, n, 1]` and of shape `[m, n]` as `[m, n, 1]`.
+pub fn dstack<T, StorageType>(
+    arrays: &[&dyn AsStrided<StorageType>],
+) -> Result<Array<StorageType, ContiguousView>, String>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+{
+    stack_promoted(arrays, 2, &[&[0, 1, 2], &[0, 2], &[2]])
+}
+
+/// Splits `array` into views along `axis` that share its storage, with the
+/// semantics of NumPy's `split`. A [`Sections::Count`] must divide the
+/// length of the axis evenly.
+pub fn split<T, StorageType, ViewType, SectionsType>(
+    array: &Array<StorageType, ViewType>,
+    sections: SectionsType,
+    axis: isize,
+) -> Result<Vec<Array<StorageType, StridedView>>, String>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+    SectionsType: Into<Sections>,
+{
+    let sections = sections.into();
+    if let Sections::Count(n) = sections {
+        let axis = normalize_axis(axis, array.ndim())?;
+        if n != 0 && !array.shape()[axis].is_multiple_of(n) {
+            return Err(format!(
+                "split: axis {} of shape {:?} cannot be divided into {} equal sections",
+                axis,
+                array.shape(),
+                n
+            ));
+        }
+    }
+    array_split(array, sections, axis)
+}
+
+/// Like [`split`], but a [`Sections::Count`] need not divide the length of
+/// the axis: the first `len % n` views get one element more than the rest.
+pub fn array_split<T, StorageType, ViewType, SectionsType>(
+    array: &Array<StorageType, ViewType>,
+    sections: SectionsType,
+    axis: isize,
+) -> Result<Vec<Array<StorageType, StridedView>>, String>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+    SectionsType: Into<Sections>,
+{
+    let axis = normalize_axis(axis, array.ndim())?;
+    let len = array.shape()[axis];
+    let bounds = match sections.into() {
+        Sections::Count(0) => {
+            return Err(format!(
+                "array_split: cannot split axis {} of shape {:?} into 0 sections",
+                axis,
+                array.shape()
+            ))
+        }
+        Sections::Count(n) => {
+            let (size, extra) = (len / n, len % n);
+            (0..n)
+                .scan(0, |start, idx| {
+                    let end = *start + size + (idx < extra) as usize;
+                    let bounds = (*start, end);
+                    *start = end;
+                    Some(bounds)
+                })
+                .collect::<Vec<_>>()
+        }
+        Sections::Indices(indices) => {
+            let mut points = vec![0];
+            points.extend(indices.iter().map(|&idx| idx.min(len)));
+            points.push(len);
+            points
+                .windows(2)
+                .map(|pair| (pair[0], pair[1].max(pair[0])))
+                .collect()
+        }
+    };
+    Ok(bounds
+        .into_iter()
+        .map(|(start, end)| narrow(array, axis, start, end))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+    use crate::test_utils::arange;
+    use crate::thread_safe_storage::ThreadSafeStorage;
+
+    #[test]
+    fn concatenate_along_axes() {
+        let a = arange(&[2, 2]);
+        let b = arange(&[2, 3]);
+        let c = concatenate(&[&a, &b], 1).unwrap();
+        assert_eq!(c.shape(), &[2, 5]);
+        assert_eq!(c.to_vec(), vec![0, 1, 0, 1, 2, 2, 3, 3, 4, 5]);
+
+        let c = concatenate(&[&a, &a, &a], -2).unwrap();
+        assert_eq!(c.shape(), &[6, 2]);
+        assert_eq!(c.to_vec()[8..], [0, 1, 2, 3]);
+
+        let c = concatenate(&[&b.t(), &a], 0).unwrap();
+        assert_eq!(c.to_vec(), vec![0, 3, 1, 4, 2, 5, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn concatenate_errors_name_axis_and_shapes() {
+        let a = arange(&[2, 2]);
+        let b = arange(&[3, 3]);
+        let err = concatenate(&[&a, &b], 1).err().unwrap();
+        assert!(err.contains("axis 1") && err.contains("axis 0"), "{}", err);
+        assert!(err.contains("[2, 2]") && err.contains("[3, 3]"), "{}", err);
+        assert!(concatenate(&[&a, &arange(&[4])], 0).is_err());
+        assert!(concatenate(&[&a], 2).is_err());
+        assert!(concatenate::<i32, ThreadSafeStorage<i32>>(&[], 0).is_err());
+    }
+
+    #[test]
+    fn stack_adds_an_axis() {
+        let a = arange(&[2, 3]);
+        let b = arange(&[2, 3]);
+        let c = stack(&[&a, &b], 0).unwrap();
+        assert_eq!(c.shape(), &[2, 2, 3]);
+        let c = stack(&[&a, &b], -1).unwrap();
+        assert_eq!(c.shape(), &[2, 3, 2]);
+        assert_eq!(c.to_vec()[..4], [0, 0, 1, 1]);
+
+        let err = stack(&[&a, &a.t()], 1).err().unwrap();
+        assert!(err.contains("[3, 2]") && err.contains("axis 1"), "{}", err);
+        assert!(stack(&[&a], 3).is_err());
+    }
+
+    #[test]
+    fn h_v_and_d_stack() {
+        let v = arange(&[3]);
+        assert_eq!(hstack(&[&v, &v]).unwrap().shape(), &[6]);
+        let stacked = vstack(&[&v, &v]).unwrap();
+        assert_eq!(stacked.shape(), &[2, 3]);
+        assert_eq!(stacked.to_vec(), vec![0, 1, 2, 0, 1, 2]);
+        let stacked = dstack(&[&v, &v]).unwrap();
+        assert_eq!(stacked.shape(), &[1, 3, 2]);
+        let reversed = v.slice(s![..;-1]);
+        let stacked = vstack(&[&v, &reversed]).unwrap();
+        assert_eq!(stacked.to_vec(), vec![0, 1, 2, 2, 1, 0]);
+
+        let m = arange(&[2, 2]);
+        let stacked = hstack(&[&m, &m]).unwrap();
+        assert_eq!(stacked.to_vec(), vec![0, 1, 0, 1, 2, 3, 2, 3]);
+        assert_eq!(vstack(&[&m, &arange(&[2])]).unwrap().shape(), &[3, 2]);
+        assert_eq!(dstack(&[&m, &m]).unwrap().shape(), &[2, 2, 2]);
+        assert!(vstack(&[&m, &v]).is_err());
+    }
+
+    #[test]
+    fn split_returns_views() {
+        let a = arange(&[6, 2]);
+        let pieces = split(&a, 3, 0).unwrap();
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(pieces[1].to_vec(), vec![4, 5, 6, 7]);
+
+        let mut last = pieces.into_iter().last().unwrap();
+        last.fill(0);
+        assert_eq!(a.to_vec()[8..], [0, 0, 0, 0]);
+
+        let err = split(&a, 4, 0).err().unwrap();
+        assert!(err.contains("axis 0") && err.contains("[6, 2]"), "{}", err);
+        let pieces = split(&a.t(), [1, 5], 1).unwrap();
+        let shapes = pieces
+            .iter()
+            .map(|p| p.shape().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(shapes, vec![vec![2, 1], vec![2, 4], vec![2, 1]]);
+        assert_eq!(pieces[2].to_vec(), vec![0, 0]);
+    }
+
+    #[test]
+    fn array_split_uneven() {
+        let a = arange(&[7]);
+        let sizes = array_split(&a, 3, 0)
+            .unwrap()
+            .iter()
+            .map(|piece| piece.numel())
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, vec![3, 2, 2]);
+
+        let pieces = array_split(&a, vec![5, 2, 9], -1).unwrap();
+        let sizes = pieces.iter().map(|p| p.numel()).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![5, 0, 5, 0]);
+        assert_eq!(pieces[2].to_vec(), vec![2, 3, 4, 5, 6]);
+        assert!(array_split(&a, 0, 0).is_err());
+        assert!(array_split(&a, 2, 1).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use num_traits::Num;
+
+use crate::arithmetic::zip_values;
+use crate::array::Array;
+use crate::gemm::{gemm, MatrixLayout};
+use crate::s;
+use crate::shape_utils::broadcast_shapes;
+use crate::slice::NewAxis;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView, StridedView};
+
+impl<T, StorageType, ViewType> Array<StorageType, ViewType>
+where
+    T: Num + Clone + 'static,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    /// Matrix product with the semantics of NumPy's `@` operator.
+    ///
+    /// 1-D operands are promoted to matrices by prepending (left) or
+    /// appending (right) an axis of length 1, which is removed again from the
+    /// result. Leading dimensions of N-D operands are treated as a stack of
+    /// matrices and are broadcast against each other.
+    pub fn matmul<RhsView: ArrayView>(
+        &self,
+        rhs: &Array<StorageType, RhsView>,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        if self.ndim() == 0 || rhs.ndim() == 0 {
+            return Err("matmul: operands must have at least one dimension".to_string());
+        }
+
+        let lhs_view = StridedView::from_view(self.view());
+        let rhs_view = StridedView::from_view(rhs.view());
+        let lhs_view = if self.ndim() == 1 {
+            lhs_view.slice(s![NewAxis])?
+        } else {
+            lhs_view
+        };
+        let rhs_view = if rhs.ndim() == 1 {
+            rhs_view.slice(s![.., NewAxis])?
+        } else {
+            rhs_view
+        };
+
+        let (lhs_batch, lhs_matrix) = lhs_view.shape().split_at(lhs_view.ndim() - 2);
+        let (rhs_batch, rhs_matrix) = rhs_view.shape().split_at(rhs_view.ndim() - 2);
+        let (m, k, n) = (lhs_matrix[0], lhs_matrix[1], rhs_matrix[1]);
+        if rhs_matrix[0] != k {
+            return Err(format!(
+                "matmul: shapes {:?} and {:?} are not aligned: {} (dim {}) != {} (dim {})",
+                self.shape(),
+                rhs.shape(),
+                k,
+                self.ndim() - 1,
+                rhs_matrix[0],
+                rhs.ndim().saturating_sub(2)
+            ));
+        }
+
+        let batch = broadcast_shapes(&[lhs_batch, rhs_batch])?;
+        let lhs_view = lhs_view.broadcast_to([&batch[..], &[m, k]].concat())?;
+        let rhs_view = rhs_view.broadcast_to([&batch[..], &[k, n]].concat())?;
+        let nb = batch.len();
+        let lhs_offsets =
+            StridedView::new_with_offset(&batch, &lhs_view.stride()[..nb], lhs_view.offset());
+        let rhs_offsets =
+            StridedView::new_with_offset(&batch, &rhs_view.stride()[..nb], rhs_view.offset());
+
+        let mut data = Vec::new();
+        data.resize_with(lhs_offsets.numel() * m * n, T::zero);
+        {
+            let lhs_storage = self
+                .storage()
+                .storage_get()
+                .map_err(|err| err.to_string())?;
+            let rhs_storage = if self.storage().storage_ptr_eq(rhs.storage()) {
+                None
+            } else {
+                Some(rhs.storage().storage_get().map_err(|err| err.to_string())?)
+            };
+            let rhs_slice = rhs_storage.as_deref().unwrap_or(&lhs_storage[..]);
+
+            let chunks = data.chunks_mut((m * n).max(1));
+            for ((lhs_offset, rhs_offset), out) in lhs_offsets
+                .translate_iter()
+                .zip(rhs_offsets.translate_iter())
+                .zip(chunks)
+            {
+                let a_layout = MatrixLayout {
+                    offset: lhs_offset,
+                    row_stride: lhs_view.stride()[nb],
+                    col_stride: lhs_view.stride()[nb + 1],
+                };
+                let b_layout = MatrixLayout {
+                    offset: rhs_offset,
+                    row_stride: rhs_view.stride()[nb],
+                    col_stride: rhs_view.stride()[nb + 1],
+                };
+                gemm(m, n, k, &lhs_storage, a_layout, rhs_slice, b_layout, out);
+            }
+        }
+
+        let mut shape = batch;
+        if self.ndim() > 1 {
+            shape.push(m);
+        }
+        if rhs.ndim() > 1 {
+            shape.push(n);
+        }
+        Ok(Array::from_parts(
+            StorageType::from(data),
+            ContiguousView::new(shape),
+        ))
+    }
+
+    /// Dot product with the semantics of NumPy's `dot`.
+    ///
+    /// For 1-D and 2-D operands this is the inner and matrix product. If
+    /// either operand is 0-D it is an element-wise product. Otherwise it is a
+    /// sum product over the last axis of `self` and the second-to-last axis
+    /// of `rhs`, as computed by [`Array::tensordot`].
+    pub fn dot<RhsView: ArrayView>(
+        &self,
+        rhs: &Array<StorageType, RhsView>,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        if self.ndim() == 0 || rhs.ndim() == 0 {
+            broadcast_shapes(&[self.shape(), rhs.shape()])?;
+            return Ok(zip_values(self, rhs, |a, b| a * b));
+        }
+        if self.ndim() <= 2 && rhs.ndim() <= 2 {
+            return self.matmul(rhs);
+        }
+
+        let contracted = rhs.ndim().saturating_sub(2) as isize;
+        self.tensordot(rhs, ([-1], [contracted]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_complex::Complex;
+
+    use super::*;
+    use crate::test_utils::arange;
+    use crate::thread_safe_storage::ThreadSafeStorage;
+    use crate::thread_unsafe_storage::ThreadUnsafeStorage;
+
+    #[test]
+    fn matmul_2d() {
+        let a = arange(&[2, 3]);
+        let b = arange(&[3, 2]);
+        let c = a.matmul(&b).unwrap();
+        assert_eq!(c.shape(), &[2, 2]);
+        assert_eq!(c.to_vec(), vec![10, 13, 28, 40]);
+    }
+
+    #[test]
+    fn matmul_transposed_operands() {
+        let a = arange(&[3, 2]);
+        let c = a.t().matmul(&a).unwrap();
+        assert_eq!(c.to_vec(), vec![20, 26, 26, 35]);
+        let c = a.matmul(&a.t()).unwrap();
+        assert_eq!(c.shape(), &[3, 3]);
+        assert_eq!(c.to_vec(), vec![1, 3, 5, 3, 13, 23, 5, 23, 41]);
+    }
+
+    #[test]
+    fn matmul_vectors() {
+        let v = arange(&[3]);
+        let inner = v.matmul(&v).unwrap();
+        assert!(inner.shape().is_empty());
+        assert_eq!(inner.item(), 5);
+
+        let m = arange(&[3, 2]);
+        assert_eq!(v.matmul(&m).unwrap().to_vec(), vec![10, 13]);
+        assert_eq!(m.t().matmul(&v).unwrap().to_vec(), vec![10, 13]);
+    }
+
+    #[test]
+    fn matmul_batched_broadcast() {
+        let a = arange(&[2, 1, 2, 3]);
+        let b = arange(&[3, 3, 2]);
+        let c = a.matmul(&b).unwrap();
+        assert_eq!(c.shape(), &[2, 3, 2, 2]);
+        for i in 0..2 {
+            for j in 0..3 {
+                let expected = a.slice(s![i, 0]).matmul(&b.slice(s![j])).unwrap().to_vec();
+                assert_eq!(c.slice(s![i, j]).to_vec(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn matmul_errors() {
+        let a = arange(&[2, 3]);
+        assert!(a.matmul(&a).is_err());
+        assert!(a.matmul(&arange(&[])).is_err());
+        assert!(arange(&[2, 2, 3]).matmul(&arange(&[3, 3, 2])).is_err());
+    }
+
+    #[test]
+    fn matmul_empty_inner_dimension() {
+        let c = arange(&[2, 0]).matmul(&arange(&[0, 3])).unwrap();
+        assert_eq!(c.to_vec(), vec![0; 6]);
+    }
+
+    #[test]
+    fn dot_nd() {
+        let a = arange(&[2, 3, 4]);
+        let b = arange(&[5, 4, 2]);
+        let c = a.dot(&b).unwrap();
+        assert_eq!(c.shape(), &[2, 3, 5, 2]);
+        let expected = a.slice(s![1, 2]).matmul(&b.slice(s![3])).unwrap().to_vec();
+        assert_eq!(c.slice(s![1, 2, 3]).to_vec(), expected);
+    }
+
+    #[test]
+    fn dot_nd_empty_contraction() {
+        let c = arange(&[2, 3, 0]).dot(&arange(&[0, 2])).unwrap();
+        assert_eq!(c.shape(), &[2, 3, 2]);
+        assert_eq!(c.to_vec(), vec![0; 12]);
+    }
+
+    #[test]
+    fn dot_scalar_and_vector() {
+        let a = Array::from_parts(
+            ThreadUnsafeStorage::from(vec![1.5f64, 2.0, 3.0, 4.0]),
+            ContiguousView::new([2, 2]),
+        );
+        let scalar = Array::from_parts(
+            ThreadUnsafeStorage::from(vec![2.0f64]),
+            ContiguousView::new([]),
+        );
+        assert_eq!(a.dot(&scalar).unwrap().to_vec(), vec![3.0, 4.0, 6.0, 8.0]);
+        let v = a.slice(s![0]);
+        assert_eq!(a.dot(&v).unwrap().to_vec(), vec![6.25, 12.5]);
+        assert!(a.dot(&a.reshape([4usize])).is_err());
+    }
+
+    #[test]
+    fn matmul_complex() {
+        let i = Complex::new(0.0, 1.0);
+        let one = Complex::new(1.0, 0.0);
+        let a = Array::from_parts(
+            ThreadSafeStorage::from(vec![one, i, -i, one]),
+            ContiguousView::new([2, 2]),
+        );
+        let c = a.matmul(&a.t()).unwrap();
+        assert_eq!(c.to_vec(), vec![one + i * i, -i + i, -i + i, i * i + one]);
+        let identity = Array::<ThreadSafeStorage<Complex<f64>>, ContiguousView>::identity(2);
+        assert_eq!(identity.dot(&a).unwrap().to_vec(), a.to_vec());
+    }
+}
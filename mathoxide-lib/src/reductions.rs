@@ -0,0 +1,441 @@
+use num_traits::{FromPrimitive, Num};
+
+use crate::array::Array;
+use crate::shape_utils::reduced_shape;
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView, StridedView};
+
+/// How NaN values are treated by the ordering reductions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// A NaN anywhere in a reduced block makes the result NaN, and the
+    /// position of the first NaN is reported by the argument reductions.
+    Propagate,
+    /// NaN values are skipped. A block that contains only NaN values reduces
+    /// to NaN, and is an error for the argument reductions.
+    Ignore,
+}
+
+/// Running extremum of a reduced block, tracking the first NaN separately so
+/// either policy can be applied once the block has been seen in full.
+#[derive(Clone)]
+struct Extremum<T> {
+    best: Option<(T, usize)>,
+    nan: Option<(T, usize)>,
+}
+
+impl<T: Clone> Extremum<T> {
+    fn resolve(self, nan_policy: NanPolicy) -> Result<(T, usize), String> {
+        match (nan_policy, self.nan, self.best) {
+            (NanPolicy::Propagate, Some(nan), _) => Ok(nan),
+            (_, _, Some(best)) => Ok(best),
+            (NanPolicy::Ignore, Some(nan), None) => Ok(nan),
+            (_, None, None) => Err("zero-size array has no minimum or maximum".to_string()),
+        }
+    }
+}
+
+/// Folds the elements of `array` over `axes` into one accumulator per output
+/// element. Besides the element, `f` receives the position of the element
+/// within its reduced block, counted in row-major order.
+///
+/// Returns the output shape together with the accumulators in row-major order.
+pub(crate) fn fold_axes<T, StorageType, ViewType, A, F>(
+    array: &Array<StorageType, ViewType>,
+    axes: Option<&[isize]>,
+    keepdims: bool,
+    init: A,
+    mut f: F,
+) -> Result<(Vec<usize>, Vec<A>), String>
+where
+    T: Num,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+    A: Clone,
+    F: FnMut(&mut A, usize, &T),
+{
+    let (out_shape, reduced) = reduced_shape(array.shape(), axes, keepdims)?;
+
+    // Walking the input alongside a view of the output with zero strides on
+    // the reduced axes maps every input element to its accumulator.
+    let kept_shape = array
+        .shape()
+        .iter()
+        .zip(reduced.iter())
+        .map(|(&dim, &is_reduced)| if is_reduced { 1 } else { dim })
+        .collect::<Vec<usize>>();
+    let kept_view = ContiguousView::new(&kept_shape);
+    let out_stride = kept_view
+        .stride()
+        .iter()
+        .zip(reduced.iter())
+        .map(|(&stride, &is_reduced)| if is_reduced { 0 } else { stride })
+        .collect::<Vec<usize>>();
+    let out_view = StridedView::new(array.shape(), out_stride);
+
+    let mut acc = vec![init; kept_view.numel()];
+    let mut counts = vec![0usize; kept_view.numel()];
+    let storage = array
+        .storage()
+        .storage_get()
+        .map_err(|err| err.to_string())?;
+    for (i, o) in array.view().translate_iter().zip(out_view.translate_iter()) {
+        f(&mut acc[o], counts[o], &storage[i]);
+        counts[o] += 1;
+    }
+
+    Ok((out_shape, acc))
+}
+
+impl<T, StorageType, ViewType> Array<StorageType, ViewType>
+where
+    T: Num + Clone,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    /// Sums the elements over `axis`, or over every element if `axis` is `None`.
+    pub fn sum(
+        &self,
+        axis: Option<&[isize]>,
+        keepdims: bool,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        let (shape, data) = fold_axes(self, axis, keepdims, T::zero(), |acc, _, x| {
+            *acc = std::mem::replace(acc, T::zero()) + x.clone()
+        })?;
+        Ok(Array::from_parts(
+            StorageType::from(data),
+            ContiguousView::new(shape),
+        ))
+    }
+
+    /// Multiplies the elements over `axis`, or over every element if `axis`
+    /// is `None`.
+    pub fn prod(
+        &self,
+        axis: Option<&[isize]>,
+        keepdims: bool,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        let (shape, data) = fold_axes(self, axis, keepdims, T::one(), |acc, _, x| {
+            *acc = std::mem::replace(acc, T::zero()) * x.clone()
+        })?;
+        Ok(Array::from_parts(
+            StorageType::from(data),
+            ContiguousView::new(shape),
+        ))
+    }
+}
+
+impl<T, StorageType, ViewType> Array<StorageType, ViewType>
+where
+    T: Num + Clone + PartialOrd,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    fn extremum(
+        &self,
+        axes: Option<&[isize]>,
+        keepdims: bool,
+        is_better: fn(&T, &T) -> bool,
+    ) -> Result<(Vec<usize>, Vec<Extremum<T>>), String> {
+        let init = Extremum {
+            best: None,
+            nan: None,
+        };
+        fold_axes(self, axes, keepdims, init, |acc, position, x| {
+            // Only NaN is unordered with respect to itself.
+            let is_nan = x.partial_cmp(x).is_none();
+            if is_nan {
+                if acc.nan.is_none() {
+                    acc.nan = Some((x.clone(), position));
+                }
+            } else if acc.best.as_ref().is_none_or(|(best, _)| is_better(x, best)) {
+                acc.best = Some((x.clone(), position));
+            }
+        })
+    }
+
+    fn reduce_extremum(
+        &self,
+        axis: Option<&[isize]>,
+        keepdims: bool,
+        nan_policy: NanPolicy,
+        is_better: fn(&T, &T) -> bool,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        let (shape, acc) = self.extremum(axis, keepdims, is_better)?;
+        let data = acc
+            .into_iter()
+            .map(|e| e.resolve(nan_policy).map(|(value, _)| value))
+            .collect::<Result<Vec<T>, String>>()?;
+        Ok(Array::from_parts(
+            StorageType::from(data),
+            ContiguousView::new(shape),
+        ))
+    }
+
+    fn arg_extremum(
+        &self,
+        axis: Option<isize>,
+        nan_policy: NanPolicy,
+        is_better: fn(&T, &T) -> bool,
+    ) -> Result<Array<StorageType::Rebind<usize>, ContiguousView>, String> {
+        let axes = axis.map(|axis| [axis]);
+        let (shape, acc) = self.extremum(axes.as_ref().map(|a| &a[..]), false, is_better)?;
+        let data = acc
+            .into_iter()
+            .map(|e| match (nan_policy, e.nan.is_some(), e.best.is_none()) {
+                (NanPolicy::Ignore, true, true) => Err("all-NaN slice encountered".to_string()),
+                _ => e.resolve(nan_policy).map(|(_, position)| position),
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+        Ok(Array::from_parts(
+            StorageType::Rebind::<usize>::from(data),
+            ContiguousView::new(shape),
+        ))
+    }
+
+    /// Minimum of the elements over `axis`, or over every element if `axis`
+    /// is `None`.
+    pub fn min(
+        &self,
+        axis: Option<&[isize]>,
+        keepdims: bool,
+        nan_policy: NanPolicy,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        self.reduce_extremum(axis, keepdims, nan_policy, |x, best| x < best)
+    }
+
+    /// Maximum of the elements over `axis`, or over every element if `axis`
+    /// is `None`.
+    pub fn max(
+        &self,
+        axis: Option<&[isize]>,
+        keepdims: bool,
+        nan_policy: NanPolicy,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        self.reduce_extremum(axis, keepdims, nan_policy, |x, best| x > best)
+    }
+
+    /// Index of the first minimum along `axis`. If `axis` is `None` the result
+    /// holds a single flat row-major index, which can be turned into per-axis
+    /// indices with [`unravel_index`](crate::shape_utils::unravel_index).
+    pub fn argmin(
+        &self,
+        axis: Option<isize>,
+        nan_policy: NanPolicy,
+    ) -> Result<Array<StorageType::Rebind<usize>, ContiguousView>, String> {
+        self.arg_extremum(axis, nan_policy, |x, best| x < best)
+    }
+
+    /// Index of the first maximum along `axis`. If `axis` is `None` the result
+    /// holds a single flat row-major index, which can be turned into per-axis
+    /// indices with [`unravel_index`](crate::shape_utils::unravel_index).
+    pub fn argmax(
+        &self,
+        axis: Option<isize>,
+        nan_policy: NanPolicy,
+    ) -> Result<Array<StorageType::Rebind<usize>, ContiguousView>, String> {
+        self.arg_extremum(axis, nan_policy, |x, best| x > best)
+    }
+}
+
+impl<T, StorageType, ViewType> Array<StorageType, ViewType>
+where
+    T: Num + Clone + FromPrimitive,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    /// Averages the elements over `axis`, or over every element if `axis` is
+    /// `None`. Integer types use integer division.
+    pub fn mean(
+        &self,
+        axis: Option<&[isize]>,
+        keepdims: bool,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        let sum = self.sum(axis, keepdims)?;
+        let count = if sum.numel() == 0 {
+            0
+        } else {
+            self.numel() / sum.numel()
+        };
+        if count == 0 {
+            return Err("cannot compute the mean of an empty reduction".to_string());
+        }
+        let count = T::from_usize(count)
+            .ok_or_else(|| format!("{} cannot be represented as an element", count))?;
+        let data = sum
+            .to_vec()
+            .into_iter()
+            .map(|x| x / count.clone())
+            .collect::<Vec<T>>();
+        Ok(Array::from_parts(
+            StorageType::from(data),
+            ContiguousView::new(sum.shape()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+    use crate::test_utils::arange;
+    use crate::thread_unsafe_storage::ThreadUnsafeStorage;
+
+    #[test]
+    fn sum_all() {
+        let array = arange(&[2, 3]);
+        let total = array.sum(None, false).unwrap();
+        assert!(total.shape().is_empty());
+        assert_eq!(total.item(), 15);
+        assert_eq!(array.sum(None, true).unwrap().shape(), &[1, 1]);
+    }
+
+    #[test]
+    fn sum_over_axes() {
+        let array = arange(&[2, 3, 4]);
+        let summed = array.sum(Some(&[1]), false).unwrap();
+        assert_eq!(summed.shape(), &[2, 4]);
+        assert_eq!(summed.to_vec(), vec![12, 15, 18, 21, 48, 51, 54, 57]);
+
+        let summed = array.sum(Some(&[0, -1]), true).unwrap();
+        assert_eq!(summed.shape(), &[1, 3, 1]);
+        assert_eq!(summed.to_vec(), vec![60, 92, 124]);
+    }
+
+    #[test]
+    fn sum_strided() {
+        let array = arange(&[2, 3]);
+        let summed = array.t().slice(s![..;-1]).sum(Some(&[-1]), false).unwrap();
+        assert_eq!(summed.to_vec(), vec![7, 5, 3]);
+    }
+
+    #[test]
+    fn prod_over_axis() {
+        let array = arange(&[2, 3]) + 1;
+        assert_eq!(
+            array.prod(Some(&[0]), false).unwrap().to_vec(),
+            vec![4, 10, 18]
+        );
+        assert_eq!(array.prod(None, false).unwrap().item(), 720);
+    }
+
+    #[test]
+    fn sum_and_prod_of_empty() {
+        let array = arange(&[0, 3]);
+        assert_eq!(
+            array.sum(Some(&[0]), false).unwrap().to_vec(),
+            vec![0, 0, 0]
+        );
+        assert_eq!(array.prod(None, false).unwrap().item(), 1);
+    }
+
+    #[test]
+    fn mean_over_axis() {
+        let array = Array::from_parts(
+            ThreadUnsafeStorage::from(vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0]),
+            ContiguousView::new([2, 3]),
+        );
+        assert_eq!(array.mean(None, false).unwrap().item(), 3.5);
+        assert_eq!(
+            array.mean(Some(&[0]), true).unwrap().to_vec(),
+            vec![2.5, 3.5, 4.5]
+        );
+        assert_eq!(
+            array.mean(Some(&[1]), false).unwrap().to_vec(),
+            vec![2.0, 5.0]
+        );
+    }
+
+    fn floats(values: &[f64], shape: &[usize]) -> Array<ThreadUnsafeStorage<f64>, ContiguousView> {
+        Array::from_parts(
+            ThreadUnsafeStorage::from(values.to_vec()),
+            ContiguousView::new(shape),
+        )
+    }
+
+    #[test]
+    fn min_max_over_axes() {
+        let array = floats(&[3.0, 1.0, 4.0, 1.0, 5.0, 9.0], &[2, 3]);
+        let policy = NanPolicy::Propagate;
+        assert_eq!(array.max(None, false, policy).unwrap().item(), 9.0);
+        assert_eq!(array.min(None, false, policy).unwrap().item(), 1.0);
+        assert_eq!(
+            array.min(Some(&[0]), false, policy).unwrap().to_vec(),
+            vec![1.0, 1.0, 4.0]
+        );
+        let max = array.max(Some(&[-1]), true, policy).unwrap();
+        assert_eq!(max.shape(), &[2, 1]);
+        assert_eq!(max.to_vec(), vec![4.0, 9.0]);
+    }
+
+    #[test]
+    fn argmin_argmax_indices() {
+        let array = floats(&[3.0, 1.0, 4.0, 1.0, 5.0, 9.0], &[2, 3]);
+        let policy = NanPolicy::Propagate;
+        assert_eq!(array.argmax(None, policy).unwrap().item(), 5);
+        assert_eq!(array.argmin(None, policy).unwrap().item(), 1);
+        assert_eq!(
+            array.argmin(Some(0), policy).unwrap().to_vec(),
+            vec![1, 0, 0]
+        );
+        assert_eq!(array.argmax(Some(-1), policy).unwrap().to_vec(), vec![2, 2]);
+        assert_eq!(
+            array.t().argmax(Some(0), policy).unwrap().to_vec(),
+            vec![2, 2]
+        );
+    }
+
+    #[test]
+    fn nan_policies() {
+        let array = floats(&[1.0, f64::NAN, 3.0, f64::NAN, f64::NAN, 0.0], &[2, 3]);
+        let max = array.max(Some(&[1]), false, NanPolicy::Propagate).unwrap();
+        assert!(max.to_vec().iter().all(|x| x.is_nan()));
+        let max = array.max(Some(&[1]), false, NanPolicy::Ignore).unwrap();
+        assert_eq!(max.to_vec(), vec![3.0, 0.0]);
+        assert_eq!(
+            array
+                .argmax(Some(1), NanPolicy::Propagate)
+                .unwrap()
+                .to_vec(),
+            vec![1, 0]
+        );
+        assert_eq!(array.argmin(None, NanPolicy::Ignore).unwrap().item(), 5);
+
+        let all_nan = floats(&[f64::NAN, f64::NAN], &[2]);
+        assert!(all_nan
+            .min(None, false, NanPolicy::Ignore)
+            .unwrap()
+            .item()
+            .is_nan());
+        assert!(all_nan.argmin(None, NanPolicy::Ignore).is_err());
+    }
+
+    #[test]
+    fn extremum_of_integers() {
+        let array = arange(&[3, 4]);
+        assert_eq!(
+            array
+                .max(Some(&[0]), false, NanPolicy::Ignore)
+                .unwrap()
+                .to_vec(),
+            vec![8, 9, 10, 11]
+        );
+        assert_eq!(array.argmin(None, NanPolicy::Ignore).unwrap().item(), 0);
+    }
+
+    #[test]
+    fn extremum_of_empty() {
+        let array = arange(&[0, 3]);
+        assert!(array.min(None, false, NanPolicy::Propagate).is_err());
+        assert!(array.argmax(Some(0), NanPolicy::Propagate).is_err());
+        assert!(array.argmax(Some(2), NanPolicy::Propagate).is_err());
+    }
+
+    #[test]
+    fn reduction_errors() {
+        let array = arange(&[2, 3]);
+        assert!(array.sum(Some(&[2]), false).is_err());
+        assert!(array.prod(Some(&[0, -2]), false).is_err());
+        assert!(arange(&[0]).mean(None, false).is_err());
+    }
+}
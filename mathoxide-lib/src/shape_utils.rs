@@ -87,3 +87,151 @@ where
         .map(|x| x.unwrap_known().unwrap_or(inferred))
         .collect::<Vec<usize>>())
 }
+
+/// Computes the shape that all of `shapes` broadcast to, following the NumPy
+/// broadcasting rules: shapes are aligned on their trailing dimensions and
+/// each pair of dimensions must either be equal or contain a 1.
+pub fn broadcast_shapes(shapes: &[&[usize]]) -> Result<Vec<usize>, String> {
+    let ndim = shapes.iter().map(|shape| shape.len()).max().unwrap_or(0);
+    let mut result = vec![1; ndim];
+
+    for shape in shapes {
+        let pad = ndim - shape.len();
+        for (axis, &dim) in shape.iter().enumerate() {
+            let out = &mut result[pad + axis];
+            if *out == 1 {
+                *out = dim;
+            } else if dim != 1 && dim != *out {
+                return Err(format!(
+                    "shapes {:?} cannot be broadcast together: mismatch at axis {}",
+                    shapes,
+                    pad + axis
+                ));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Converts a possibly negative axis into an index into a shape with `ndim`
+/// dimensions.
+pub fn normalize_axis(axis: isize, ndim: usize) -> Result<usize, String> {
+    let normalized = if axis < 0 { axis + ndim as isize } else { axis };
+    if normalized < 0 || normalized >= ndim as isize {
+        return Err(format!(
+            "axis {} is out of bounds for array of dimension {}",
+            axis, ndim
+        ));
+    }
+    Ok(normalized as usize)
+}
+
+/// Computes the output shape of a reduction over `axes` of `shape`, along
+/// with a mask of the reduced dimensions. `None` reduces over every axis.
+pub fn reduced_shape(
+    shape: &[usize],
+    axes: Option<&[isize]>,
+    keepdims: bool,
+) -> Result<(Vec<usize>, Vec<bool>), String> {
+    let mut reduced = vec![axes.is_none(); shape.len()];
+    for &axis in axes.unwrap_or(&[]) {
+        let axis = normalize_axis(axis, shape.len())?;
+        if reduced[axis] {
+            return Err(format!("duplicate value in axes {:?}", axes.unwrap()));
+        }
+        reduced[axis] = true;
+    }
+
+    let out_shape = shape
+        .iter()
+        .zip(reduced.iter())
+        .filter_map(|(&dim, &is_reduced)| match (is_reduced, keepdims) {
+            (false, _) => Some(dim),
+            (true, true) => Some(1),
+            (true, false) => None,
+        })
+        .collect();
+    Ok((out_shape, reduced))
+}
+
+/// Converts a flat row-major index into one index per axis of `shape`.
+pub fn unravel_index(index: usize, shape: &[usize]) -> Result<Vec<usize>, String> {
+    let numel: usize = shape.iter().product();
+    if index >= numel {
+        return Err(format!(
+            "index {} is out of bounds for array with shape {:?}",
+            index, shape
+        ));
+    }
+    let mut rest = index;
+    let mut result = vec![0; shape.len()];
+    for (axis, &dim) in shape.iter().enumerate().rev() {
+        result[axis] = rest % dim;
+        rest /= dim;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn broadcast_same_shapes() {
+        assert_eq!(broadcast_shapes(&[&[2, 3], &[2, 3]]).unwrap(), vec![2, 3]);
+    }
+
+    #[test]
+    fn broadcast_trailing_alignment() {
+        assert_eq!(
+            broadcast_shapes(&[&[8, 1, 6, 1], &[7, 1, 5]]).unwrap(),
+            vec![8, 7, 6, 5]
+        );
+        assert_eq!(broadcast_shapes(&[&[3], &[4, 1], &[]]).unwrap(), vec![4, 3]);
+    }
+
+    #[test]
+    fn broadcast_zero_sized() {
+        assert_eq!(broadcast_shapes(&[&[0, 3], &[1, 3]]).unwrap(), vec![0, 3]);
+        assert!(broadcast_shapes(&[&[0], &[2]]).is_err());
+    }
+
+    #[test]
+    fn broadcast_mismatch() {
+        assert!(broadcast_shapes(&[&[2, 3], &[3, 2]]).is_err());
+    }
+
+    #[test]
+    fn normalize_negative_axis() {
+        assert_eq!(normalize_axis(-1, 3).unwrap(), 2);
+        assert_eq!(normalize_axis(1, 3).unwrap(), 1);
+        assert!(normalize_axis(3, 3).is_err());
+        assert!(normalize_axis(-4, 3).is_err());
+    }
+
+    #[test]
+    fn reduced_shape_axes() {
+        let (shape, mask) = reduced_shape(&[2, 3, 4], Some(&[0, -1]), false).unwrap();
+        assert_eq!(shape, vec![3]);
+        assert_eq!(mask, vec![true, false, true]);
+        let (shape, _) = reduced_shape(&[2, 3, 4], Some(&[1]), true).unwrap();
+        assert_eq!(shape, vec![2, 1, 4]);
+        let (shape, _) = reduced_shape(&[2, 3, 4], None, false).unwrap();
+        assert!(shape.is_empty());
+        assert!(reduced_shape(&[2, 3], Some(&[1, -1]), false).is_err());
+    }
+
+    #[test]
+    fn unravel_flat_index() {
+        assert_eq!(unravel_index(0, &[2, 3, 4]).unwrap(), vec![0, 0, 0]);
+        assert_eq!(unravel_index(23, &[2, 3, 4]).unwrap(), vec![1, 2, 3]);
+        assert_eq!(unravel_index(0, &[]).unwrap(), Vec::<usize>::new());
+        assert!(unravel_index(6, &[2, 3]).is_err());
+    }
+
+    #[test]
+    fn broadcast_no_shapes() {
+        assert_eq!(broadcast_shapes(&[]).unwrap(), Vec::<usize>::new());
+    }
+}
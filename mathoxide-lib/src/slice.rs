@@ -0,0 +1,279 @@
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+/// Marker that inserts a new axis of length 1 when used in a slice spec.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NewAxis;
+
+/// A single element of a slice spec, usually built through the [`s!`] macro.
+///
+/// Slices follow NumPy semantics: negative `start`/`end` count from the end of
+/// the axis, bounds are clamped to the axis and a negative `step` walks the
+/// axis backwards, with `start` and `end` defaulting to the last element and
+/// one before the first element respectively. An `inclusive` end, as built
+/// from `a..=b`, also selects the element at `end` in either direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SliceInfoElem {
+    Slice {
+        start: Option<isize>,
+        end: Option<isize>,
+        inclusive: bool,
+        step: isize,
+    },
+    Index(isize),
+    NewAxis,
+}
+
+impl SliceInfoElem {
+    pub fn step_by(self, step: isize) -> Self {
+        match self {
+            SliceInfoElem::Slice {
+                start,
+                end,
+                inclusive,
+                ..
+            } => SliceInfoElem::Slice {
+                start,
+                end,
+                inclusive,
+                step,
+            },
+            _ => panic!("a step can only be applied to a range"),
+        }
+    }
+}
+
+impl From<RangeFull> for SliceInfoElem {
+    fn from(_: RangeFull) -> Self {
+        SliceInfoElem::Slice {
+            start: None,
+            end: None,
+            inclusive: false,
+            step: 1,
+        }
+    }
+}
+
+impl From<NewAxis> for SliceInfoElem {
+    fn from(_: NewAxis) -> Self {
+        SliceInfoElem::NewAxis
+    }
+}
+
+macro_rules! impl_slice_info_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for SliceInfoElem {
+                fn from(val: $t) -> Self {
+                    SliceInfoElem::Index(val as isize)
+                }
+            }
+
+            impl From<Range<$t>> for SliceInfoElem {
+                fn from(val: Range<$t>) -> Self {
+                    SliceInfoElem::Slice {
+                        start: Some(val.start as isize),
+                        end: Some(val.end as isize),
+                        inclusive: false,
+                        step: 1,
+                    }
+                }
+            }
+
+            impl From<RangeFrom<$t>> for SliceInfoElem {
+                fn from(val: RangeFrom<$t>) -> Self {
+                    SliceInfoElem::Slice {
+                        start: Some(val.start as isize),
+                        end: None,
+                        inclusive: false,
+                        step: 1,
+                    }
+                }
+            }
+
+            impl From<RangeTo<$t>> for SliceInfoElem {
+                fn from(val: RangeTo<$t>) -> Self {
+                    SliceInfoElem::Slice {
+                        start: None,
+                        end: Some(val.end as isize),
+                        inclusive: false,
+                        step: 1,
+                    }
+                }
+            }
+
+            impl From<RangeInclusive<$t>> for SliceInfoElem {
+                fn from(val: RangeInclusive<$t>) -> Self {
+                    SliceInfoElem::Slice {
+                        start: Some(*val.start() as isize),
+                        end: Some(*val.end() as isize),
+                        inclusive: true,
+                        step: 1,
+                    }
+                }
+            }
+
+            impl From<RangeToInclusive<$t>> for SliceInfoElem {
+                fn from(val: RangeToInclusive<$t>) -> Self {
+                    SliceInfoElem::Slice {
+                        start: None,
+                        end: Some(val.end as isize),
+                        inclusive: true,
+                        step: 1,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_slice_info_from!(isize, usize, i32);
+
+/// Resolves a slice against an axis of length `len`, returning the index of
+/// the first selected element and the number of selected elements.
+pub(crate) fn resolve_slice(
+    start: Option<isize>,
+    end: Option<isize>,
+    inclusive: bool,
+    step: isize,
+    len: usize,
+) -> (isize, usize) {
+    let len = len as isize;
+    let wrap = |v: isize| if v < 0 { v + len } else { v };
+    // An inclusive end is made exclusive only now that the direction is
+    // known, and after wrapping so that `..=-1` still means the last element.
+    let end = end.map(|v| {
+        if inclusive {
+            wrap(v) + step.signum()
+        } else {
+            wrap(v)
+        }
+    });
+    if step > 0 {
+        let start = start.map_or(0, |v| wrap(v).clamp(0, len));
+        let end = end.map_or(len, |v| v.clamp(0, len));
+        let count = if end > start {
+            (end - start + step - 1) / step
+        } else {
+            0
+        };
+        (start, count as usize)
+    } else {
+        let start = start.map_or(len - 1, |v| wrap(v).clamp(-1, len - 1));
+        let end = end.map_or(-1, |v| v.clamp(-1, len - 1));
+        let count = if start > end {
+            (start - end - step - 1) / -step
+        } else {
+            0
+        };
+        (start, count as usize)
+    }
+}
+
+/// Builds a slice spec for [`Array::slice`](crate::array::Array::slice).
+///
+/// Each comma separated element is either a range, a range followed by
+/// `;step`, a single index, which drops the axis, or [`NewAxis`]. Axes that
+/// are not mentioned are taken in full.
+///
+/// ```
+/// use mathoxide_lib::s;
+/// use mathoxide_lib::slice::{NewAxis, SliceInfoElem};
+///
+/// let info = s![1..4, ..;-2, NewAxis, 3];
+/// assert_eq!(info[3], SliceInfoElem::Index(3));
+/// ```
+#[macro_export]
+macro_rules! s {
+    (@parse [$($acc:expr),*] $r:expr ; $step:expr , $($rest:tt)*) => {
+        $crate::s!(@parse [$($acc,)* $crate::slice::SliceInfoElem::from($r).step_by($step as isize)] $($rest)*)
+    };
+    (@parse [$($acc:expr),*] $r:expr ; $step:expr) => {
+        $crate::s!(@parse [$($acc,)* $crate::slice::SliceInfoElem::from($r).step_by($step as isize)])
+    };
+    (@parse [$($acc:expr),*] $r:expr , $($rest:tt)*) => {
+        $crate::s!(@parse [$($acc,)* $crate::slice::SliceInfoElem::from($r)] $($rest)*)
+    };
+    (@parse [$($acc:expr),*] $r:expr) => {
+        $crate::s!(@parse [$($acc,)* $crate::slice::SliceInfoElem::from($r)])
+    };
+    (@parse [$($acc:expr),*]) => {
+        [$($acc),*]
+    };
+    ($($t:tt)*) => {
+        $crate::s!(@parse [] $($t)*)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn macro_elements() {
+        let info = s![1..4, ..;2, 3, NewAxis, ..=-1, -2..;-1];
+        assert_eq!(
+            info,
+            [
+                SliceInfoElem::Slice {
+                    start: Some(1),
+                    end: Some(4),
+                    inclusive: false,
+                    step: 1
+                },
+                SliceInfoElem::Slice {
+                    start: None,
+                    end: None,
+                    inclusive: false,
+                    step: 2
+                },
+                SliceInfoElem::Index(3),
+                SliceInfoElem::NewAxis,
+                SliceInfoElem::Slice {
+                    start: None,
+                    end: Some(-1),
+                    inclusive: true,
+                    step: 1
+                },
+                SliceInfoElem::Slice {
+                    start: Some(-2),
+                    end: None,
+                    inclusive: false,
+                    step: -1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn macro_trailing_comma() {
+        assert_eq!(s![.., 1,].len(), 2);
+    }
+
+    #[test]
+    fn resolve_positive_step() {
+        assert_eq!(resolve_slice(Some(1), Some(4), false, 1, 5), (1, 3));
+        assert_eq!(resolve_slice(None, None, false, 2, 5), (0, 3));
+        assert_eq!(resolve_slice(Some(-2), None, false, 1, 5), (3, 2));
+        assert_eq!(resolve_slice(Some(3), Some(100), false, 1, 5), (3, 2));
+        assert_eq!(resolve_slice(Some(4), Some(1), false, 1, 5), (4, 0));
+    }
+
+    #[test]
+    fn resolve_negative_step() {
+        assert_eq!(resolve_slice(None, None, false, -1, 5), (4, 5));
+        assert_eq!(resolve_slice(None, None, false, -2, 5), (4, 3));
+        assert_eq!(resolve_slice(Some(3), Some(0), false, -1, 5), (3, 3));
+        assert_eq!(resolve_slice(Some(10), Some(-10), false, -3, 5), (4, 2));
+        assert_eq!(resolve_slice(Some(0), Some(3), false, -1, 5).1, 0);
+    }
+
+    #[test]
+    fn resolve_inclusive_end() {
+        assert_eq!(resolve_slice(Some(1), Some(3), true, 1, 5), (1, 3));
+        assert_eq!(resolve_slice(None, Some(-1), true, 1, 5), (0, 5));
+        assert_eq!(resolve_slice(Some(3), Some(0), true, -1, 4), (3, 4));
+        assert_eq!(resolve_slice(None, Some(-3), true, -1, 4), (3, 3));
+        assert_eq!(resolve_slice(Some(3), Some(0), true, -2, 4), (3, 2));
+        assert_eq!(resolve_slice(Some(0), Some(3), true, -1, 4).1, 0);
+    }
+}
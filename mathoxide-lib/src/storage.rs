@@ -14,10 +14,12 @@ pub trait Storage: From<Vec<Self::Stored>> + Clone {
     type GuardMut<'a>: DerefMut<Target = [Self::Stored]>
     where
         Self: 'a;
+    type Rebind<U>: Storage<Stored = U>;
 
     fn storage_get<'a>(&'a self) -> Result<Self::Guard<'a>, &str>;
     fn storage_get_mut<'a>(&'a mut self) -> Result<Self::GuardMut<'a>, &str>;
     fn storage_len(&self) -> Result<usize, &str>;
+    fn storage_ptr_eq(&self, other: &Self) -> bool;
 }
 
 impl<T> Storage for ThreadSafeStorage<T> {
@@ -30,6 +32,7 @@ impl<T> Storage for ThreadSafeStorage<T> {
     where
         Self: 'a,
     = ThreadSafeStorageGuardMut<'a, T>;
+    type Rebind<U> = ThreadSafeStorage<U>;
 
     fn storage_get<'a>(&'a self) -> Result<Self::Guard<'a>, &str> {
         self.get()
@@ -42,6 +45,10 @@ impl<T> Storage for ThreadSafeStorage<T> {
     fn storage_len(&self) -> Result<usize, &str> {
         self.len()
     }
+
+    fn storage_ptr_eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
 }
 
 impl<T> Storage for ThreadUnsafeStorage<T> {
@@ -54,6 +61,7 @@ impl<T> Storage for ThreadUnsafeStorage<T> {
     where
         Self: 'a,
     = RefMut<'a, [T]>;
+    type Rebind<U> = ThreadUnsafeStorage<U>;
 
     fn storage_get<'a>(&'a self) -> Result<Self::Guard<'a>, &str> {
         Ok(Ref::map(self.get()?, |r| r.as_slice()))
@@ -66,6 +74,10 @@ impl<T> Storage for ThreadUnsafeStorage<T> {
     fn storage_len(&self) -> Result<usize, &str> {
         self.len()
     }
+
+    fn storage_ptr_eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
 }
 
 #[cfg(test)]
@@ -105,6 +117,17 @@ mod test {
         assert_eq!(storage.storage_get().unwrap()[0], 101);
     }
 
+    #[test]
+    fn ptr_eq_detects_shared_storage() {
+        let storage = ThreadSafeStorage::from(vec![1, 2, 3]);
+        assert!(storage.storage_ptr_eq(&storage.clone()));
+        assert!(!storage.storage_ptr_eq(&ThreadSafeStorage::from(vec![1, 2, 3])));
+
+        let storage = ThreadUnsafeStorage::from(vec![1, 2, 3]);
+        assert!(storage.storage_ptr_eq(&storage.clone()));
+        assert!(!storage.storage_ptr_eq(&ThreadUnsafeStorage::from(vec![1, 2, 3])));
+    }
+
     #[test]
     fn thread_unsafe_generics() {
         let mut storage = ThreadUnsafeStorage::from(vec![1, 2, 3, 4, 5]);
@@ -0,0 +1,333 @@
+use num_traits::Num;
+
+use crate::arithmetic::zip_values;
+use crate::array::Array;
+use crate::shape_utils::{broadcast_shapes, infer_shape, normalize_axis};
+use crate::storage::Storage;
+use crate::views::{ArrayView, ContiguousView};
+
+/// Axes contracted by [`Array::tensordot`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TensorAxes {
+    /// The last `n` axes of the left operand against the first `n` axes of
+    /// the right operand, in order.
+    Count(usize),
+    /// Explicit pairs of axes, the first list indexing the left operand and
+    /// the second the right operand. Negative axes count from the end.
+    Pairs(Vec<isize>, Vec<isize>),
+}
+
+impl From<usize> for TensorAxes {
+    fn from(n: usize) -> Self {
+        TensorAxes::Count(n)
+    }
+}
+
+impl<LhsAxes, RhsAxes> From<(LhsAxes, RhsAxes)> for TensorAxes
+where
+    LhsAxes: AsRef<[isize]>,
+    RhsAxes: AsRef<[isize]>,
+{
+    fn from((lhs, rhs): (LhsAxes, RhsAxes)) -> Self {
+        TensorAxes::Pairs(lhs.as_ref().to_vec(), rhs.as_ref().to_vec())
+    }
+}
+
+/// Normalizes `axes` against `shape`, rejecting repeated axes.
+fn contracted_axes(axes: &[isize], shape: &[usize]) -> Result<Vec<usize>, String> {
+    let mut result = Vec::with_capacity(axes.len());
+    for &axis in axes {
+        let axis = normalize_axis(axis, shape.len())?;
+        if result.contains(&axis) {
+            return Err(format!(
+                "tensordot: repeated axis {} for shape {:?}",
+                axis, shape
+            ));
+        }
+        result.push(axis);
+    }
+    Ok(result)
+}
+
+impl<T, StorageType, ViewType> Array<StorageType, ViewType>
+where
+    T: Num + Clone + 'static,
+    StorageType: Storage<Stored = T>,
+    ViewType: ArrayView,
+{
+    /// Sum product over the given axes of `self` and `rhs`, with the
+    /// semantics of NumPy's `tensordot`.
+    ///
+    /// The result has the remaining axes of `self` followed by the remaining
+    /// axes of `rhs`. Both operands are permuted and flattened to matrices,
+    /// so the contraction itself is a single matrix product.
+    pub fn tensordot<RhsView, AxesType>(
+        &self,
+        rhs: &Array<StorageType, RhsView>,
+        axes: AxesType,
+    ) -> Result<Array<StorageType, ContiguousView>, String>
+    where
+        RhsView: ArrayView,
+        AxesType: Into<TensorAxes>,
+    {
+        let (lhs_axes, rhs_axes) = match axes.into() {
+            TensorAxes::Count(n) => {
+                if n > self.ndim() || n > rhs.ndim() {
+                    return Err(format!(
+                        "tensordot: cannot contract {} axes of shapes {:?} and {:?}",
+                        n,
+                        self.shape(),
+                        rhs.shape()
+                    ));
+                }
+                (
+                    (self.ndim() - n..self.ndim()).collect::<Vec<usize>>(),
+                    (0..n).collect::<Vec<usize>>(),
+                )
+            }
+            TensorAxes::Pairs(lhs_axes, rhs_axes) => {
+                if lhs_axes.len() != rhs_axes.len() {
+                    return Err(format!(
+                        "tensordot: axes {:?} and {:?} do not pair up",
+                        lhs_axes, rhs_axes
+                    ));
+                }
+                (
+                    contracted_axes(&lhs_axes, self.shape())?,
+                    contracted_axes(&rhs_axes, rhs.shape())?,
+                )
+            }
+        };
+        for (&lhs_axis, &rhs_axis) in lhs_axes.iter().zip(rhs_axes.iter()) {
+            if self.shape()[lhs_axis] != rhs.shape()[rhs_axis] {
+                return Err(format!(
+                    "tensordot: shapes {:?} and {:?} are not aligned: {} (dim {}) != {} (dim {})",
+                    self.shape(),
+                    rhs.shape(),
+                    self.shape()[lhs_axis],
+                    lhs_axis,
+                    rhs.shape()[rhs_axis],
+                    rhs_axis
+                ));
+            }
+        }
+
+        let lhs_free = (0..self.ndim())
+            .filter(|axis| !lhs_axes.contains(axis))
+            .collect::<Vec<usize>>();
+        let rhs_free = (0..rhs.ndim())
+            .filter(|axis| !rhs_axes.contains(axis))
+            .collect::<Vec<usize>>();
+        let m = lhs_free
+            .iter()
+            .map(|&axis| self.shape()[axis])
+            .product::<usize>();
+        let k = lhs_axes
+            .iter()
+            .map(|&axis| self.shape()[axis])
+            .product::<usize>();
+        let n = rhs_free
+            .iter()
+            .map(|&axis| rhs.shape()[axis])
+            .product::<usize>();
+
+        let lhs_matrix = self
+            .permute_axes([&lhs_free[..], &lhs_axes[..]].concat())
+            .reshape([m, k]);
+        let rhs_matrix = rhs
+            .permute_axes([&rhs_axes[..], &rhs_free[..]].concat())
+            .reshape([k, n]);
+        let product = lhs_matrix.matmul(&rhs_matrix)?;
+
+        let shape = lhs_free
+            .iter()
+            .map(|&axis| self.shape()[axis])
+            .chain(rhs_free.iter().map(|&axis| rhs.shape()[axis]))
+            .collect::<Vec<usize>>();
+        if shape.is_empty() {
+            // A full contraction leaves a single element, and `reshape` does
+            // not accept the empty shape of a 0-D array.
+            return Ok(Array::from_parts(
+                product.storage().clone(),
+                ContiguousView::new(shape),
+            ));
+        }
+        let shape = infer_shape(shape, product.numel())?;
+        if shape.iter().product::<usize>() != product.numel() {
+            return Err(format!(
+                "tensordot: result shape {:?} does not hold {} elements",
+                shape,
+                product.numel()
+            ));
+        }
+        Ok(product.reshape(shape))
+    }
+
+    /// Inner product with the semantics of NumPy's `inner`: a sum product
+    /// over the last axes of `self` and `rhs`, or an element-wise product if
+    /// either is 0-D.
+    pub fn inner<RhsView: ArrayView>(
+        &self,
+        rhs: &Array<StorageType, RhsView>,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        if self.ndim() == 0 || rhs.ndim() == 0 {
+            broadcast_shapes(&[self.shape(), rhs.shape()])?;
+            return Ok(zip_values(self, rhs, |a, b| a * b));
+        }
+        self.tensordot(rhs, ([-1], [-1]))
+    }
+
+    /// Outer product of the flattened `self` and `rhs`, a matrix with one
+    /// row per element of `self` and one column per element of `rhs`.
+    pub fn outer<RhsView: ArrayView>(
+        &self,
+        rhs: &Array<StorageType, RhsView>,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        self.reshape([self.numel(), 1])
+            .matmul(&rhs.reshape([1, rhs.numel()]))
+    }
+
+    /// Kronecker product, a block array where each element of `self` scales
+    /// a copy of `rhs`. The operand with fewer dimensions is padded with
+    /// leading axes of length 1.
+    pub fn kron<RhsView: ArrayView>(
+        &self,
+        rhs: &Array<StorageType, RhsView>,
+    ) -> Result<Array<StorageType, ContiguousView>, String> {
+        if self.ndim() == 0 || rhs.ndim() == 0 {
+            return Ok(zip_values(self, rhs, |a, b| a * b));
+        }
+        let ndim = self.ndim().max(rhs.ndim());
+        let pad = |shape: &[usize]| {
+            let mut padded = vec![1; ndim - shape.len()];
+            padded.extend_from_slice(shape);
+            padded
+        };
+        let (lhs_shape, rhs_shape) = (pad(self.shape()), pad(rhs.shape()));
+
+        // Interleaving the axes of both operands turns the Kronecker product
+        // into a broadcast product of shape [a0, b0, a1, b1, ...].
+        let lhs_interleaved = lhs_shape
+            .iter()
+            .flat_map(|&dim| [dim, 1])
+            .collect::<Vec<usize>>();
+        let rhs_interleaved = rhs_shape
+            .iter()
+            .flat_map(|&dim| [1, dim])
+            .collect::<Vec<usize>>();
+        let shape = lhs_shape
+            .iter()
+            .zip(rhs_shape.iter())
+            .map(|(&a, &b)| a * b)
+            .collect::<Vec<usize>>();
+        let product = zip_values(
+            &self.reshape(lhs_interleaved),
+            &rhs.reshape(rhs_interleaved),
+            |a, b| a * b,
+        );
+        Ok(product.reshape(shape))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+    use crate::test_utils::arange;
+    use crate::thread_safe_storage::ThreadSafeStorage;
+
+    #[test]
+    fn tensordot_count() {
+        let a = arange(&[3, 4, 5]);
+        let b = arange(&[4, 5, 2]);
+        let c = a.tensordot(&b, 2).unwrap();
+        assert_eq!(c.shape(), &[3, 2]);
+        let expected = a
+            .reshape([3usize, 20])
+            .matmul(&b.reshape([20usize, 2]))
+            .unwrap()
+            .to_vec();
+        assert_eq!(c.to_vec(), expected);
+
+        let outer = a.tensordot(&b, 0).unwrap();
+        assert_eq!(outer.shape(), &[3, 4, 5, 4, 5, 2]);
+        assert!(a.tensordot(&b, 4).is_err());
+    }
+
+    #[test]
+    fn tensordot_pairs() {
+        let a = arange(&[3, 4, 5]);
+        let b = arange(&[5, 3, 2]);
+        let c = a.tensordot(&b, ([0, 2], [1, 0])).unwrap();
+        assert_eq!(c.shape(), &[4, 2]);
+        let mut expected = vec![0; 8];
+        for j in 0..4i64 {
+            for l in 0..2i64 {
+                for i in 0..3i64 {
+                    for k in 0..5i64 {
+                        expected[(j * 2 + l) as usize] +=
+                            (i * 20 + j * 5 + k) * (k * 6 + i * 2 + l);
+                    }
+                }
+            }
+        }
+        assert_eq!(c.to_vec(), expected);
+
+        let full = a.tensordot(&a, ([-3, -2, -1], [0, 1, 2])).unwrap();
+        assert!(full.shape().is_empty());
+        assert_eq!(full.item(), (0..60).map(|x| x * x).sum::<i64>());
+    }
+
+    #[test]
+    fn tensordot_errors_name_shapes() {
+        let a = arange(&[3, 4]);
+        let err = a.tensordot(&a, ([0], [1])).err().unwrap();
+        assert!(err.contains("[3, 4]"), "{}", err);
+        assert!(a.tensordot(&a, ([0, 0], [0, 0])).is_err());
+        assert!(a.tensordot(&a, ([0], [0, 1])).is_err());
+        assert!(a.tensordot(&a, ([2], [0])).is_err());
+    }
+
+    #[test]
+    fn inner_products() {
+        let v = arange(&[3]);
+        assert_eq!(v.inner(&v).unwrap().item(), 5);
+        let a = arange(&[2, 3]);
+        let b = arange(&[4, 3]);
+        let c = a.inner(&b).unwrap();
+        assert_eq!(c.shape(), &[2, 4]);
+        assert_eq!(c.to_vec(), a.matmul(&b.t()).unwrap().to_vec());
+        let scalar =
+            Array::from_parts(ThreadSafeStorage::from(vec![2i64]), ContiguousView::new([]));
+        assert_eq!(a.inner(&scalar).unwrap().to_vec(), vec![0, 2, 4, 6, 8, 10]);
+    }
+
+    #[test]
+    fn outer_flattens() {
+        let a = arange(&[2, 2]);
+        let b = arange(&[3]);
+        let c = a.outer(&b).unwrap();
+        assert_eq!(c.shape(), &[4, 3]);
+        assert_eq!(c.slice(s![3]).to_vec(), vec![0, 3, 6]);
+    }
+
+    #[test]
+    fn kron_blocks() {
+        let a = arange(&[2, 2]);
+        let eye = Array::from_parts(
+            ThreadSafeStorage::from(vec![1i64, 0, 0, 1]),
+            ContiguousView::new([2, 2]),
+        );
+        let c = a.kron(&eye).unwrap();
+        assert_eq!(c.shape(), &[4, 4]);
+        assert_eq!(
+            c.to_vec(),
+            vec![0, 0, 1, 0, 0, 0, 0, 1, 2, 0, 3, 0, 0, 2, 0, 3]
+        );
+
+        let v = arange(&[3]);
+        let c = v.kron(&a).unwrap();
+        assert_eq!(c.shape(), &[2, 6]);
+        assert_eq!(c.slice(s![1]).to_vec(), vec![0, 0, 2, 3, 4, 6]);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::array::Array;
+use crate::storage::Storage;
+use crate::thread_safe_storage::ThreadSafeStorage;
+use crate::views::{ArrayView, ContiguousView};
+
+pub type TestArray = Array<ThreadSafeStorage<f64>, ContiguousView>;
+
+/// A contiguous array holding `0, 1, 2, ...` in row-major order.
+pub fn arange(shape: &[usize]) -> Array<ThreadSafeStorage<i64>, ContiguousView> {
+    let view = ContiguousView::new(shape);
+    let storage = ThreadSafeStorage::from((0..view.numel() as i64).collect::<Vec<i64>>());
+    Array::from_parts(storage, view)
+}
+
+pub fn array(values: &[f64], shape: &[usize]) -> TestArray {
+    Array::from_parts(
+        ThreadSafeStorage::from(values.to_vec()),
+        ContiguousView::new(shape),
+    )
+}
+
+pub fn assert_close<S, V>(actual: &Array<S, V>, expected: &[f64], tol: f64)
+where
+    S: Storage<Stored = f64>,
+    V: ArrayView,
+{
+    let actual = actual.to_vec();
+    assert_eq!(actual.len(), expected.len());
+    for (x, y) in actual.iter().zip(expected.iter()) {
+        assert!(
+            (x - y).abs() <= tol * (1.0 + y.abs()),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-pub(crate) struct ThreadSafeStorageGuard<'a, T> {
+pub struct ThreadSafeStorageGuard<'a, T> {
     _guard: RwLockReadGuard<'a, Vec<T>>,
     r: *const [T],
 }
@@ -23,7 +23,7 @@ impl<'a, T> std::ops::Deref for ThreadSafeStorageGuard<'a, T> {
     }
 }
 
-pub(crate) struct ThreadSafeStorageGuardMut<'a, T> {
+pub struct ThreadSafeStorageGuardMut<'a, T> {
     _guard: RwLockWriteGuard<'a, Vec<T>>,
     r: *mut [T],
 }
@@ -53,35 +53,40 @@ impl<'a, T> std::ops::DerefMut for ThreadSafeStorageGuardMut<'a, T> {
     }
 }
 
-pub(crate) struct ThreadSafeStorage<T> {
+/// Reference-counted storage behind an `RwLock`, shareable across threads.
+pub struct ThreadSafeStorage<T> {
     data: Arc<RwLock<Vec<T>>>,
 }
 
 impl<T> ThreadSafeStorage<T> {
-    pub fn new(v: Vec<T>) -> Self {
+    pub(crate) fn new(v: Vec<T>) -> Self {
         Self {
             data: Arc::new(RwLock::new(v)),
         }
     }
 
-    pub fn get(&self) -> Result<ThreadSafeStorageGuard<T>, &str> {
+    pub(crate) fn get(&self) -> Result<ThreadSafeStorageGuard<T>, &str> {
         Ok(ThreadSafeStorageGuard::new(
             self.data.read().map_err(|_| "RwLock was poisoned")?,
         ))
     }
 
-    pub fn get_mut(&mut self) -> Result<ThreadSafeStorageGuardMut<T>, &str> {
+    pub(crate) fn get_mut(&mut self) -> Result<ThreadSafeStorageGuardMut<T>, &str> {
         Ok(ThreadSafeStorageGuardMut::new(
             self.data.write().map_err(|_| "RwLock was poisoned")?,
         ))
     }
 
-    pub fn len(&self) -> Result<usize, &str> {
+    pub(crate) fn len(&self) -> Result<usize, &str> {
         self.data
             .read()
             .map_err(|_| "RwLock was poisoned")
             .map(|v| v.len())
     }
+
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
 }
 
 impl<T> From<Vec<T>> for ThreadSafeStorage<T> {
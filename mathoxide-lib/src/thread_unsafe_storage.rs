@@ -1,35 +1,40 @@
 use std::cell::{Ref, RefCell, RefMut};
 use std::rc::Rc;
 
-pub(crate) struct ThreadUnsafeStorage<T> {
+/// Reference-counted storage behind a `RefCell`, for single-threaded use.
+pub struct ThreadUnsafeStorage<T> {
     data: Rc<RefCell<Vec<T>>>,
 }
 
 impl<T> ThreadUnsafeStorage<T> {
-    pub fn new(v: Vec<T>) -> Self {
+    pub(crate) fn new(v: Vec<T>) -> Self {
         Self {
             data: Rc::new(RefCell::new(v)),
         }
     }
 
-    pub fn get(&self) -> Result<Ref<Vec<T>>, &str> {
+    pub(crate) fn get(&self) -> Result<Ref<Vec<T>>, &str> {
         self.data
             .try_borrow()
             .map_err(|_| "Array is borrowed immutable")
     }
 
-    pub fn get_mut(&mut self) -> Result<RefMut<Vec<T>>, &str> {
+    pub(crate) fn get_mut(&mut self) -> Result<RefMut<Vec<T>>, &str> {
         self.data
             .try_borrow_mut()
             .map_err(|_| "Array is already borrowed")
     }
 
-    pub fn len(&self) -> Result<usize, &str> {
+    pub(crate) fn len(&self) -> Result<usize, &str> {
         self.data
             .try_borrow()
             .map_err(|_| "Array is borrowed immutable")
             .map(|v| v.len())
     }
+
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
 }
 
 impl<T> From<Vec<T>> for ThreadUnsafeStorage<T> {
@@ -23,3 +23,55 @@ impl Iterator for ContiguousViewIterator {
         }
     }
 }
+
+pub struct StridedViewIterator {
+    shape: Vec<usize>,
+    stride: Vec<usize>,
+    index: Vec<usize>,
+    curr: usize,
+    remaining: usize,
+}
+
+impl StridedViewIterator {
+    pub fn new(shape: &[usize], stride: &[usize], offset: usize) -> Self {
+        Self {
+            shape: shape.to_vec(),
+            stride: stride.to_vec(),
+            index: vec![0; shape.len()],
+            curr: offset,
+            remaining: shape.iter().product(),
+        }
+    }
+}
+
+impl Iterator for StridedViewIterator {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let curr = self.curr;
+        self.remaining -= 1;
+
+        // Advance the row-major index, keeping the memory offset in sync so
+        // that every step only touches the axes that actually rolled over.
+        for axis in (0..self.shape.len()).rev() {
+            self.index[axis] += 1;
+            self.curr = self.curr.wrapping_add(self.stride[axis]);
+            if self.index[axis] < self.shape[axis] {
+                break;
+            }
+            self.curr = self
+                .curr
+                .wrapping_sub(self.stride[axis].wrapping_mul(self.shape[axis]));
+            self.index[axis] = 0;
+        }
+
+        Some(curr)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
@@ -1,7 +1,8 @@
-use crate::view_iters::ContiguousViewIterator;
+use crate::slice::{resolve_slice, SliceInfoElem};
+use crate::view_iters::{ContiguousViewIterator, StridedViewIterator};
 
 pub trait ArrayView {
-    type IterType;
+    type IterType: Iterator<Item = usize>;
 
     fn translate<ListType: AsRef<[usize]>>(&self, idx: ListType) -> usize;
     fn checked_translate<ListType: AsRef<[usize]>>(&self, idx: ListType) -> Option<usize> {
@@ -25,6 +26,7 @@ pub trait ArrayView {
     fn translate_iter(&self) -> Self::IterType;
 }
 
+#[derive(Clone, Debug)]
 pub struct ContiguousView {
     shape: Vec<usize>,
     offset: usize,
@@ -96,9 +98,224 @@ impl ArrayView for ContiguousView {
     }
 }
 
+/// A view with an arbitrary shape, stride and offset.
+///
+/// Strides are stored as `usize` but are interpreted as two's complement
+/// values, so a stride of `usize::MAX` steps one element backwards. All
+/// address arithmetic is therefore done with wrapping operations.
+#[derive(Clone, Debug)]
+pub struct StridedView {
+    shape: Vec<usize>,
+    offset: usize,
+    stride: Vec<usize>,
+}
+
+impl StridedView {
+    pub fn new<ShapeType, StrideType>(shape: ShapeType, stride: StrideType) -> Self
+    where
+        ShapeType: AsRef<[usize]>,
+        StrideType: AsRef<[usize]>,
+    {
+        Self::new_with_offset(shape, stride, 0)
+    }
+
+    pub fn new_with_offset<ShapeType, StrideType>(
+        shape: ShapeType,
+        stride: StrideType,
+        offset: usize,
+    ) -> Self
+    where
+        ShapeType: AsRef<[usize]>,
+        StrideType: AsRef<[usize]>,
+    {
+        assert_eq!(
+            shape.as_ref().len(),
+            stride.as_ref().len(),
+            "shape and stride must have the same number of dimensions"
+        );
+        Self {
+            shape: shape.as_ref().to_vec(),
+            offset,
+            stride: stride.as_ref().to_vec(),
+        }
+    }
+
+    pub fn from_view<ViewType: ArrayView>(view: &ViewType) -> Self {
+        Self::new_with_offset(view.shape(), view.stride(), view.offset())
+    }
+
+    pub fn permute_axes<ListType: AsRef<[usize]>>(&self, axes: ListType) -> Result<Self, String> {
+        let axes = axes.as_ref();
+        let mut seen = vec![false; self.ndim()];
+        if axes.len() != self.ndim() {
+            return Err(format!(
+                "axes {:?} do not match array with {} dimensions",
+                axes,
+                self.ndim()
+            ));
+        }
+        for &axis in axes {
+            if axis >= self.ndim() || seen[axis] {
+                return Err(format!("axes {:?} are not a valid permutation", axes));
+            }
+            seen[axis] = true;
+        }
+        Ok(Self {
+            shape: axes.iter().map(|&axis| self.shape[axis]).collect(),
+            offset: self.offset,
+            stride: axes.iter().map(|&axis| self.stride[axis]).collect(),
+        })
+    }
+
+    /// Broadcasts the view to `shape`. Axes that are added or stretched from
+    /// length 1 get a stride of 0, so every index along them maps to the same
+    /// memory.
+    pub fn broadcast_to<ListType: AsRef<[usize]>>(&self, shape: ListType) -> Result<Self, String> {
+        let shape = shape.as_ref();
+        if shape.len() < self.ndim() {
+            return Err(format!(
+                "cannot broadcast shape {:?} to fewer dimensions {:?}",
+                self.shape, shape
+            ));
+        }
+        let pad = shape.len() - self.ndim();
+        let mut stride = vec![0; shape.len()];
+        for axis in 0..self.ndim() {
+            let (dim, target) = (self.shape[axis], shape[pad + axis]);
+            if dim == target {
+                stride[pad + axis] = self.stride[axis];
+            } else if dim != 1 {
+                return Err(format!(
+                    "cannot broadcast shape {:?} to {:?}: mismatch at axis {}",
+                    self.shape,
+                    shape,
+                    pad + axis
+                ));
+            }
+        }
+        Ok(Self {
+            shape: shape.to_vec(),
+            offset: self.offset,
+            stride,
+        })
+    }
+
+    pub fn slice<InfoType: AsRef<[SliceInfoElem]>>(&self, info: InfoType) -> Result<Self, String> {
+        let info = info.as_ref();
+        let n_axes = info
+            .iter()
+            .filter(|elem| !matches!(elem, SliceInfoElem::NewAxis))
+            .count();
+        if n_axes > self.ndim() {
+            return Err(format!(
+                "too many indices for array with {} dimensions: {} were given",
+                self.ndim(),
+                n_axes
+            ));
+        }
+
+        let mut shape = Vec::with_capacity(self.ndim() + info.len());
+        let mut stride = Vec::with_capacity(self.ndim() + info.len());
+        let mut offset = self.offset;
+        let mut axis = 0;
+        for elem in info {
+            match *elem {
+                SliceInfoElem::Slice {
+                    start,
+                    end,
+                    inclusive,
+                    step,
+                } => {
+                    if step == 0 {
+                        return Err(format!("slice step for axis {} cannot be zero", axis));
+                    }
+                    let (start, len) = resolve_slice(start, end, inclusive, step, self.shape[axis]);
+                    if len > 0 {
+                        offset =
+                            offset.wrapping_add((start as usize).wrapping_mul(self.stride[axis]));
+                    }
+                    shape.push(len);
+                    stride.push(self.stride[axis].wrapping_mul(step as usize));
+                    axis += 1;
+                }
+                SliceInfoElem::Index(idx) => {
+                    let len = self.shape[axis] as isize;
+                    let wrapped = if idx < 0 { idx + len } else { idx };
+                    if wrapped < 0 || wrapped >= len {
+                        return Err(format!(
+                            "index {} is out of bounds for axis {} with size {}",
+                            idx, axis, len
+                        ));
+                    }
+                    offset =
+                        offset.wrapping_add((wrapped as usize).wrapping_mul(self.stride[axis]));
+                    axis += 1;
+                }
+                SliceInfoElem::NewAxis => {
+                    shape.push(1);
+                    stride.push(0);
+                }
+            }
+        }
+        shape.extend_from_slice(&self.shape[axis..]);
+        stride.extend_from_slice(&self.stride[axis..]);
+
+        Ok(Self {
+            shape,
+            offset,
+            stride,
+        })
+    }
+}
+
+impl ArrayView for StridedView {
+    type IterType = StridedViewIterator;
+
+    fn translate<ListType: AsRef<[usize]>>(&self, idx: ListType) -> usize {
+        idx.as_ref()
+            .iter()
+            .zip(self.stride().iter())
+            .fold(self.offset(), |acc, (x, y)| {
+                acc.wrapping_add(x.wrapping_mul(*y))
+            })
+    }
+
+    fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn shape(&self) -> &[usize] {
+        self.shape.as_slice()
+    }
+
+    fn stride(&self) -> &[usize] {
+        self.stride.as_slice()
+    }
+
+    fn is_contiguous(&self) -> bool {
+        if self.numel() == 0 {
+            return true;
+        }
+        let mut expected = 1;
+        for (&dim, &stride) in self.shape.iter().zip(self.stride.iter()).rev() {
+            if dim != 1 && stride != expected {
+                return false;
+            }
+            expected *= dim;
+        }
+        true
+    }
+
+    fn translate_iter(&self) -> Self::IterType {
+        StridedViewIterator::new(&self.shape, &self.stride, self.offset)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::s;
+    use crate::slice::NewAxis;
 
     #[test]
     fn contiguous_view_check() {
@@ -150,4 +367,128 @@ mod test {
         assert_eq!(ContiguousView::new([2, 3]).ndim(), 2);
         assert_eq!(ContiguousView::new([2, 3, 4]).ndim(), 3);
     }
+
+    #[test]
+    fn strided_view_from_contiguous() {
+        let view = StridedView::from_view(&ContiguousView::new_with_offset([2, 3, 4], 3));
+        assert!(view.is_contiguous());
+        assert_eq!(view.stride(), &[12, 4, 1]);
+        assert!(view.translate_iter().eq(3..27));
+    }
+
+    #[test]
+    fn strided_view_transposed_iter() {
+        let view = StridedView::new([3, 2], [1, 3]);
+        assert!(!view.is_contiguous());
+        assert_eq!(
+            view.translate_iter().collect::<Vec<_>>(),
+            &[0, 3, 1, 4, 2, 5]
+        );
+        assert_eq!(view.translate([2, 1]), 5);
+    }
+
+    #[test]
+    fn strided_view_negative_stride() {
+        let view = StridedView::new_with_offset([2, 3], [3, (-1isize) as usize], 2);
+        assert!(!view.is_contiguous());
+        assert_eq!(
+            view.translate_iter().collect::<Vec<_>>(),
+            &[2, 1, 0, 5, 4, 3]
+        );
+    }
+
+    #[test]
+    fn strided_view_zero_stride() {
+        let view = StridedView::new([2, 3], [0, 1]);
+        assert!(!view.is_contiguous());
+        assert_eq!(
+            view.translate_iter().collect::<Vec<_>>(),
+            &[0, 1, 2, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn strided_view_unit_dims_are_contiguous() {
+        assert!(StridedView::new([2, 1, 3], [3, 100, 1]).is_contiguous());
+        assert!(StridedView::new([2, 0], [5, 7]).is_contiguous());
+    }
+
+    #[test]
+    fn strided_view_permute_axes() {
+        let view = StridedView::from_view(&ContiguousView::new([2, 3, 4]));
+        let permuted = view.permute_axes([2, 0, 1]).unwrap();
+        assert_eq!(permuted.shape(), &[4, 2, 3]);
+        assert_eq!(permuted.stride(), &[1, 12, 4]);
+        assert_eq!(permuted.translate([3, 1, 2]), view.translate([1, 2, 3]));
+        assert!(view.permute_axes([0, 1]).is_err());
+        assert!(view.permute_axes([0, 1, 1]).is_err());
+        assert!(view.permute_axes([0, 1, 3]).is_err());
+    }
+
+    #[test]
+    fn strided_view_slice() {
+        let view = StridedView::from_view(&ContiguousView::new([4, 5, 6]));
+        let sliced = view.slice(s![1..4, ..;2, 3]).unwrap();
+        assert_eq!(sliced.shape(), &[3, 3]);
+        assert_eq!(sliced.stride(), &[30, 12]);
+        assert_eq!(sliced.offset(), 33);
+        assert_eq!(sliced.translate([2, 1]), view.translate([3, 2, 3]));
+    }
+
+    #[test]
+    fn strided_view_slice_negative_step() {
+        let view = StridedView::from_view(&ContiguousView::new([5]));
+        let sliced = view.slice(s![..;-2]).unwrap();
+        assert_eq!(sliced.translate_iter().collect::<Vec<_>>(), &[4, 2, 0]);
+        let sliced = sliced.slice(s![1..]).unwrap();
+        assert_eq!(sliced.translate_iter().collect::<Vec<_>>(), &[2, 0]);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn strided_view_slice_inclusive_negative_step() {
+        let view = StridedView::from_view(&ContiguousView::new([4]));
+        let sliced = view.slice(s![3..=0;-1]).unwrap();
+        assert_eq!(sliced.translate_iter().collect::<Vec<_>>(), &[3, 2, 1, 0]);
+        let sliced = view.slice(s![..=1;-1]).unwrap();
+        assert_eq!(sliced.translate_iter().collect::<Vec<_>>(), &[3, 2, 1]);
+        let sliced = view.slice(s![1..=-1;2]).unwrap();
+        assert_eq!(sliced.translate_iter().collect::<Vec<_>>(), &[1, 3]);
+    }
+
+    #[test]
+    fn strided_view_slice_new_axis() {
+        let view = StridedView::from_view(&ContiguousView::new([2, 3]));
+        let sliced = view.slice(s![NewAxis, -1, NewAxis]).unwrap();
+        assert_eq!(sliced.shape(), &[1, 1, 3]);
+        assert_eq!(sliced.translate_iter().collect::<Vec<_>>(), &[3, 4, 5]);
+    }
+
+    #[test]
+    fn strided_view_slice_errors() {
+        let view = StridedView::from_view(&ContiguousView::new([2, 3]));
+        assert!(view.slice(s![0, 0, 0]).is_err());
+        assert!(view.slice(s![2]).is_err());
+        assert!(view.slice(s![-3]).is_err());
+        assert!(view.slice(s![..;0]).is_err());
+    }
+
+    #[test]
+    fn strided_view_broadcast() {
+        let view = StridedView::from_view(&ContiguousView::new_with_offset([3, 1], 2));
+        let broadcast = view.broadcast_to([2, 3, 4]).unwrap();
+        assert_eq!(broadcast.stride(), &[0, 1, 0]);
+        assert_eq!(broadcast.translate([1, 2, 3]), 4);
+        assert_eq!(broadcast.numel(), 24);
+        assert!(view.broadcast_to([3, 4, 1]).is_err());
+        assert!(view.broadcast_to([4]).is_err());
+    }
+
+    #[test]
+    fn strided_view_empty_iter() {
+        let view = StridedView::new([2, 0, 3], [0, 3, 1]);
+        assert_eq!(view.translate_iter().count(), 0);
+        let scalar = StridedView::new_with_offset([], [], 4);
+        assert_eq!(scalar.translate_iter().collect::<Vec<_>>(), &[4]);
+    }
 }